@@ -0,0 +1,77 @@
+//! Baselines for the hot paths of signing and parsing confidential transactions.
+//!
+//! Run with `cargo bench` and compare against the numbers of the base branch before merging
+//! changes to the sighash cache or the commitment types.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use elements_fun::{
+    bip143::SigHashCache, confidential::ValueCommitment, encode, hashes::Hash, AssetId,
+    AssetIssuance, OutPoint, Script, SigHashType, Transaction, TxIn, TxInWitness, TxOut, Txid,
+};
+
+fn transaction(inputs: u32) -> Transaction {
+    let asset = AssetId::from_slice(&[1; 32]).unwrap();
+
+    Transaction {
+        version: 2,
+        lock_time: 0,
+        input: (0..inputs)
+            .map(|vout| TxIn {
+                previous_output: OutPoint::new(Txid::from_slice(&[2; 32]).unwrap(), vout),
+                is_pegin: false,
+                has_issuance: false,
+                script_sig: Script::new(),
+                sequence: 0xffff_ffff,
+                asset_issuance: AssetIssuance::default(),
+                witness: TxInWitness::default(),
+            })
+            .collect(),
+        output: vec![
+            TxOut::new_explicit(asset, 100_000, Script::from(vec![0x51; 22])),
+            TxOut::new_fee(asset, 1_000),
+        ],
+    }
+}
+
+fn signature_hash(c: &mut Criterion) {
+    let script_code = Script::from(vec![0x51; 25]);
+    let mut group = c.benchmark_group("signature_hash");
+
+    for &inputs in &[1, 10, 100, 1000] {
+        let tx = transaction(inputs);
+        let mut cache = SigHashCache::new(&tx);
+        // fill the cache so only the per-input part is measured
+        cache.components();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(inputs),
+            &inputs,
+            |b, &inputs| {
+                b.iter(|| {
+                    cache.signature_hash(
+                        black_box(inputs as usize - 1),
+                        &script_code,
+                        100_000,
+                        SigHashType::ALL,
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn value_commitment(c: &mut Criterion) {
+    let commitment = ValueCommitment::from_commitment(0x08, &[3; 32]).unwrap();
+    let bytes = encode::serialize(&commitment);
+
+    c.bench_function("ValueCommitment::from_slice", |b| {
+        b.iter(|| ValueCommitment::from_slice(black_box(&bytes)).unwrap())
+    });
+    c.bench_function("ValueCommitment::consensus_decode", |b| {
+        b.iter(|| encode::deserialize::<ValueCommitment>(black_box(&bytes)).unwrap())
+    });
+}
+
+criterion_group!(benches, signature_hash, value_commitment);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+extern crate elements;
+
+use elements::confidential::{AssetCommitment, Nonce, ValueCommitment};
+use elements::encode::{deserialize, serialize, Decodable, Encodable};
+
+/// Parsing a commitment must be lossless, i.e. it must serialize back to the exact input bytes.
+fn assert_lossless<T: Decodable + Encodable>(data: &[u8]) {
+    let result: Result<T, _> = deserialize(data);
+    match result {
+        Err(_) => {}
+        Ok(commitment) => {
+            let reser = serialize(&commitment);
+            assert_eq!(data, &reser[..]);
+        }
+    }
+}
+
+fn do_test(data: &[u8]) {
+    assert_lossless::<AssetCommitment>(data);
+    assert_lossless::<ValueCommitment>(data);
+    assert_lossless::<Nonce>(data);
+}
+
+#[cfg(feature = "afl")]
+extern crate afl;
+#[cfg(feature = "afl")]
+fn main() {
+    afl::read_stdio_bytes(|data| {
+        do_test(&data);
+    });
+}
+
+#[cfg(feature = "honggfuzz")]
+#[macro_use]
+extern crate honggfuzz;
+#[cfg(feature = "honggfuzz")]
+fn main() {
+    loop {
+        fuzz!(|data| {
+            do_test(data);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn extend_vec_from_hex(hex: &str, out: &mut Vec<u8>) {
+        let mut b = 0;
+        for (idx, c) in hex.as_bytes().iter().enumerate() {
+            b <<= 4;
+            match *c {
+                b'A'...b'F' => b |= c - b'A' + 10,
+                b'a'...b'f' => b |= c - b'a' + 10,
+                b'0'...b'9' => b |= c - b'0',
+                _ => panic!("Bad hex"),
+            }
+            if (idx & 1) == 1 {
+                out.push(b);
+                b = 0;
+            }
+        }
+    }
+
+    #[test]
+    fn duplicate_crash() {
+        let mut a = Vec::new();
+        extend_vec_from_hex(
+            "0a0000000000000000000000000000000000000000000000000000000000000000",
+            &mut a,
+        );
+        super::do_test(&a);
+    }
+}
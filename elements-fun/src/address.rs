@@ -535,6 +535,56 @@ impl fmt::Debug for Address {
     }
 }
 
+/// Extract the blinding pubkey of a confidential address of any of the built-in networks.
+///
+/// Unlike parsing an [Address], this only checks what is needed to find the blinding pubkey:
+/// the checksum, the network prefix and the length of the payload. Both base58 and blech32
+/// addresses are supported. Unconfidential addresses are rejected with
+/// [`AddressError::InvalidAddress`].
+pub fn blinding_pubkey(address: &str) -> Result<secp256k1::PublicKey, AddressError> {
+    let networks = [&AddressParams::LIQUID, &AddressParams::ELEMENTS];
+
+    // Blech32.
+    let prefix = find_prefix(address);
+    if networks
+        .iter()
+        .any(|params| match_prefix(prefix, params.blech_hrp))
+    {
+        let payload = crate::blech32::decode(address)
+            .map_err(AddressError::Blech32)?
+            .1;
+        if payload.is_empty() {
+            return Err(AddressError::InvalidAddress(address.to_owned()));
+        }
+        let data = Vec::from_base32(&payload[1..]).map_err(AddressError::Blech32)?;
+        if data.len() != 53 && data.len() != 65 {
+            return Err(AddressError::InvalidWitnessProgramLength);
+        }
+
+        return secp256k1::PublicKey::from_slice(&data[..33])
+            .map_err(AddressError::InvalidBlindingPubKey);
+    }
+
+    // Base58.
+    if address.len() > 150 {
+        return Err(base58::Error::InvalidLength(address.len() * 11 / 15).into());
+    }
+    let data = base58::from_check(address)?;
+    let is_blinded = data.first().map_or(false, |prefix| {
+        networks
+            .iter()
+            .any(|params| *prefix == params.blinded_prefix)
+    });
+    if !is_blinded {
+        return Err(AddressError::InvalidAddress(address.to_owned()));
+    }
+    if data.len() != 55 {
+        return Err(base58::Error::InvalidLength(data.len()).into());
+    }
+
+    secp256k1::PublicKey::from_slice(&data[2..35]).map_err(AddressError::InvalidBlindingPubKey)
+}
+
 /// Extract the bech32 prefix.
 /// Returns the same slice when no prefix is found.
 fn find_prefix(bech32: &str) -> &str {
@@ -730,9 +780,46 @@ mod test {
 
         for addr in &vectors {
             roundtrips(addr);
+            assert_eq!(
+                blinding_pubkey(&addr.to_string()).ok(),
+                addr.blinding_pubkey,
+                "blinding pubkey of {}",
+                addr
+            );
         }
     }
 
+    #[test]
+    fn blinding_pubkey_of_actuals() {
+        let blinder = |hex: &str| PublicKey::from_str(hex).unwrap();
+
+        assert_eq!(
+            blinding_pubkey(
+                "CTEo6VKG8xbe7HnfVW9mQoWTgtgeRSPktwTLbELzGw5tV8Ngzu53EBiasFMQKVbWmKWWTAdN5AUf4M6Y"
+            ),
+            Ok(blinder(
+                "027df26b572ecf912c6da51487951ae1361cb0b631ea9e85144640e8c78c275fd0"
+            ))
+        );
+        assert_eq!(
+            blinding_pubkey("lq1qqf8er278e6nyvuwtgf39e6ewvdcnjupn9a86rzpx655y5lhkt0walu3djf9cklkxd3ryld97hu8h3xepw7sh2rlu7q45dcew5"),
+            Ok(blinder("024f91abc7cea64671cb42625ceb2e63713970332f4fa18826d5284a7ef65bdddf"))
+        );
+        assert_eq!(
+            blinding_pubkey("ex1q7gkeyjut0mrxc3j0kjlt7rmcnvsh0gt45d3fud"),
+            Err(AddressError::InvalidAddress(
+                "ex1q7gkeyjut0mrxc3j0kjlt7rmcnvsh0gt45d3fud".to_owned()
+            ))
+        );
+        assert_eq!(
+            blinding_pubkey("GqiQRsPEyJLAsEBFB5R34KHuqxDNkG3zur"),
+            Err(AddressError::InvalidAddress(
+                "GqiQRsPEyJLAsEBFB5R34KHuqxDNkG3zur".to_owned()
+            ))
+        );
+        assert!(blinding_pubkey("lq1qqf8er278e6nyvuwtgf39e6ewvdcnjupn9a86rzpx655y5lhkt0walu3djf9cklkxd3ryld97hu8h3xepw7sh2rlu7q45dcew6").is_err());
+    }
+
     #[test]
     fn test_actuals() {
         // vectors: (address, blinded?, params)
@@ -20,13 +20,78 @@
 //!
 
 use crate::{
+    confidential::ValueCommitment,
     encode::{self, Encodable},
     hash_types::SigHash,
     script::Script,
-    transaction::{SigHashType, Transaction},
+    transaction::{OutPoint, SigHashBase, SigHashType, Transaction, TxOut},
+    wally::tx_get_elements_signature_hash,
+    PubkeyHash, WPubkeyHash,
+};
+use bitcoin::secp256k1::{
+    Message, PublicKey, Secp256k1, SecretKey, Signature, Signing, Verification,
 };
 use bitcoin_hashes::{sha256d, Hash};
-use std::{io, ops::Deref};
+use std::{fmt, io, ops::Deref};
+
+/// The transaction-wide hashes committed to by the segwit v0 sighash of every input.
+///
+/// These are the exact values an external signer, e.g. hardware wallet firmware, feeds into its
+/// own sighash computation. Use `into_inner()` to get their bytes in the order they are hashed;
+/// beware that like txids, their `Display` implementation prints them reversed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionHashes {
+    pub hash_prevouts: sha256d::Hash,
+    pub hash_sequence: sha256d::Hash,
+    pub hash_issuances: sha256d::Hash,
+    pub hash_outputs: sha256d::Hash,
+}
+
+/// Everything needed to compute the sighash of a single input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningContext {
+    /// Index of the input to sign
+    pub input_index: usize,
+    /// Script code of the spent output
+    pub script_code: Script,
+    /// Serialized amount of the spent output, see
+    /// [`SigHashCache::encode_signing_data_with_amount_to`]
+    pub amount: Vec<u8>,
+    /// Sighash type to sign with
+    pub sighash_type: SigHashType,
+}
+
+impl SigningContext {
+    /// Context for an input spending an output with an explicit value.
+    pub fn explicit(
+        input_index: usize,
+        script_code: Script,
+        value: u64,
+        sighash_type: SigHashType,
+    ) -> Self {
+        SigningContext {
+            input_index,
+            script_code,
+            amount: value.to_le_bytes().to_vec(),
+            sighash_type,
+        }
+    }
+
+    /// Context for an input spending an output with a committed value.
+    pub fn confidential(
+        input_index: usize,
+        script_code: Script,
+        value: ValueCommitment,
+        sighash_type: SigHashType,
+    ) -> Self {
+        SigningContext {
+            input_index,
+            script_code,
+            amount: value.commitment().to_vec(),
+            sighash_type,
+        }
+    }
+}
 
 /// A replacement for SigHashComponents which supports all sighash modes
 pub struct SigHashCache<R: Deref<Target = Transaction>> {
@@ -40,6 +105,8 @@ pub struct SigHashCache<R: Deref<Target = Transaction>> {
     hash_outputs: Option<sha256d::Hash>,
     /// Hash of all the issunaces in this transaction, computed as required
     hash_issuances: Option<sha256d::Hash>,
+    /// Hash of the proofs of all the outputs in this transaction, computed as required
+    hash_rangeproofs: Option<sha256d::Hash>,
 }
 
 impl<R: Deref<Target = Transaction>> SigHashCache<R> {
@@ -54,6 +121,7 @@ impl<R: Deref<Target = Transaction>> SigHashCache<R> {
             hash_sequence: None,
             hash_outputs: None,
             hash_issuances: None,
+            hash_rangeproofs: None,
         }
     }
 
@@ -101,6 +169,9 @@ impl<R: Deref<Target = Transaction>> SigHashCache<R> {
     }
 
     /// Calculate hash for outputs
+    ///
+    /// Like elementsd, this is the hash of the serialized outputs without a length prefix, hence
+    /// the hash of the empty string for a transaction without outputs, not the all-zero hash.
     pub fn hash_outputs(&mut self) -> sha256d::Hash {
         let hash_output = &mut self.hash_outputs;
         let output = &self.tx.output;
@@ -113,16 +184,105 @@ impl<R: Deref<Target = Transaction>> SigHashCache<R> {
         })
     }
 
+    /// Calculate hash for the rangeproofs and surjection proofs of all outputs, which
+    /// `SIGHASH_RANGEPROOF` commits to
+    pub fn hash_rangeproofs(&mut self) -> sha256d::Hash {
+        let hash_rangeproofs = &mut self.hash_rangeproofs;
+        let output = &self.tx.output;
+        *hash_rangeproofs.get_or_insert_with(|| {
+            let mut enc = sha256d::Hash::engine();
+            for txout in output {
+                encode_proofs(txout, &mut enc).unwrap();
+            }
+            sha256d::Hash::from_engine(enc)
+        })
+    }
+
+    /// Calculate all transaction-wide hashes at once.
+    pub fn components(&mut self) -> TransactionHashes {
+        TransactionHashes {
+            hash_prevouts: self.hash_prevouts(),
+            hash_sequence: self.hash_sequence(),
+            hash_issuances: self.hash_issuances(),
+            hash_outputs: self.hash_outputs(),
+        }
+    }
+
+    /// The outpoint and sequence of the input at `input_index`, i.e. the input-specific fields
+    /// the sighash of that input commits to.
+    ///
+    /// A covenant evaluator can take these as the current input of `OP_PUSHCURRENTINPUTINDEX`
+    /// and related introspection opcodes. Returns `None` if `input_index` is out of range.
+    pub fn current_input_fields(&self, input_index: usize) -> Option<(&OutPoint, u32)> {
+        let txin = self.tx.input.get(input_index)?;
+
+        Some((&txin.previous_output, txin.sequence))
+    }
+
     /// Encode the BIP143 signing data for any flag type into a given object implementing a
     /// std::io::Write trait.
+    ///
+    /// For `SIGHASH_SINGLE`, the output hash commits to the output at `input_index`. If there is
+    /// no such output, i.e. `input_index >= tx.output.len()`, the all-zero hash is used instead,
+    /// exactly like elementsd does. There is no equivalent of the legacy `SIGHASH_SINGLE` bug.
+    ///
+    /// Under `SIGHASH_NONE`, `SIGHASH_SINGLE` and `ANYONECANPAY`, the sequence hash is the
+    /// all-zero hash as in BIP143. The sequence of the signed input is still committed to as part
+    /// of the input-specific data, so only the sequences of the other inputs can change.
+    ///
+    /// With the `SIGHASH_RANGEPROOF` flag, the hash of the rangeproofs and surjection proofs of
+    /// the signed outputs follows the output hash, like in elementsd. It is the all-zero hash if
+    /// no outputs are signed.
+    ///
+    /// Fails without writing anything if `input_index` is out of range of the transaction's
+    /// inputs.
     pub fn encode_signing_data_to<Write: io::Write>(
         &mut self,
-        mut writer: Write,
+        writer: Write,
         input_index: usize,
         script_code: &Script,
         value: u64,
         sighash_type: SigHashType,
     ) -> Result<(), encode::Error> {
+        self.encode_signing_data_with_amount_to(
+            writer,
+            input_index,
+            script_code,
+            &value.to_le_bytes(),
+            sighash_type,
+        )
+    }
+
+    /// Like [`encode_signing_data_to`], but writes the serialized `amount` of the spent output
+    /// verbatim instead of encoding an explicit value.
+    ///
+    /// `amount` is either 8 bytes for an explicit value, the 9 bytes of an explicit value
+    /// serialized like Elements does or the 33 bytes of a value commitment, exactly as the spent
+    /// output carries it. Anything else is rejected without writing anything.
+    ///
+    /// [`encode_signing_data_to`]: SigHashCache::encode_signing_data_to
+    pub fn encode_signing_data_with_amount_to<Write: io::Write>(
+        &mut self,
+        mut writer: Write,
+        input_index: usize,
+        script_code: &Script,
+        amount: &[u8],
+        sighash_type: SigHashType,
+    ) -> Result<(), encode::Error> {
+        if input_index >= self.tx.input.len() {
+            return Err(encode::Error::ParseFailed("input index out of range"));
+        }
+
+        match amount.len() {
+            8 | 33 => {}
+            9 if amount[0] == 1 => {}
+            _ => {
+                return Err(encode::Error::ParseFailed(
+                    "amount must be 8, 9 or 33 bytes long",
+                ))
+            }
+        }
+
         let zero_hash = sha256d::Hash::default();
 
         let (sighash, anyone_can_pay) = sighash_type.split_anyonecanpay_flag();
@@ -135,7 +295,7 @@ impl<R: Deref<Target = Transaction>> SigHashCache<R> {
             zero_hash.consensus_encode(&mut writer)?;
         }
 
-        if !anyone_can_pay && sighash != SigHashType::Single && sighash != SigHashType::None {
+        if !anyone_can_pay && sighash.base == SigHashBase::All {
             self.hash_sequence().consensus_encode(&mut writer)?;
         } else {
             zero_hash.consensus_encode(&mut writer)?;
@@ -155,22 +315,43 @@ impl<R: Deref<Target = Transaction>> SigHashCache<R> {
 
             txin.previous_output.consensus_encode(&mut writer)?;
             script_code.consensus_encode(&mut writer)?;
-            value.consensus_encode(&mut writer)?;
+            writer.write_all(amount)?;
             txin.sequence.consensus_encode(&mut writer)?;
             if txin.has_issuance() {
                 txin.asset_issuance.consensus_encode(&mut writer)?;
             }
         }
 
-        // hashoutputs
-        if sighash != SigHashType::Single && sighash != SigHashType::None {
-            self.hash_outputs().consensus_encode(&mut writer)?;
-        } else if sighash == SigHashType::Single && input_index < self.tx.output.len() {
-            let mut single_enc = SigHash::engine();
-            self.tx.output[input_index].consensus_encode(&mut single_enc)?;
-            SigHash::from_engine(single_enc).consensus_encode(&mut writer)?;
-        } else {
-            zero_hash.consensus_encode(&mut writer)?;
+        // hashoutputs and, with SIGHASH_RANGEPROOF, the hash of their proofs
+        let (hash_outputs, hash_rangeproofs) = match sighash.base {
+            SigHashBase::All => {
+                let hash_rangeproofs = if sighash_type.rangeproof {
+                    self.hash_rangeproofs()
+                } else {
+                    zero_hash
+                };
+                (self.hash_outputs(), hash_rangeproofs)
+            }
+            SigHashBase::Single if input_index < self.tx.output.len() => {
+                let output = &self.tx.output[input_index];
+
+                let mut single_enc = sha256d::Hash::engine();
+                output.consensus_encode(&mut single_enc)?;
+                let mut proofs_enc = sha256d::Hash::engine();
+                if sighash_type.rangeproof {
+                    encode_proofs(output, &mut proofs_enc)?;
+                }
+
+                (
+                    sha256d::Hash::from_engine(single_enc),
+                    sha256d::Hash::from_engine(proofs_enc),
+                )
+            }
+            SigHashBase::Single | SigHashBase::None => (zero_hash, zero_hash),
+        };
+        hash_outputs.consensus_encode(&mut writer)?;
+        if sighash_type.rangeproof {
+            hash_rangeproofs.consensus_encode(&mut writer)?;
         }
 
         self.tx.lock_time.consensus_encode(&mut writer)?;
@@ -179,6 +360,16 @@ impl<R: Deref<Target = Transaction>> SigHashCache<R> {
     }
 
     /// Compute the BIP143 sighash for any flag type.
+    ///
+    /// If the script being executed contains `OP_CODESEPARATOR`, `script_code` is only the part
+    /// after the last executed separator, see [`Script::script_code_after_separator`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input_index` is out of range of the transaction's inputs. Use
+    /// [`try_signature_hash`] to handle this case instead.
+    ///
+    /// [`try_signature_hash`]: SigHashCache::try_signature_hash
     pub fn signature_hash(
         &mut self,
         input_index: usize,
@@ -186,9 +377,1041 @@ impl<R: Deref<Target = Transaction>> SigHashCache<R> {
         value: u64,
         sighash_type: SigHashType,
     ) -> SigHash {
+        self.try_signature_hash(input_index, script_code, value, sighash_type)
+            .expect("input index in range")
+    }
+
+    /// Compute the BIP143 sighash for any flag type like [`signature_hash`], but without
+    /// panicking.
+    ///
+    /// Fails only if `input_index` is out of range of the transaction's inputs.
+    ///
+    /// [`signature_hash`]: SigHashCache::signature_hash
+    pub fn try_signature_hash(
+        &mut self,
+        input_index: usize,
+        script_code: &Script,
+        value: u64,
+        sighash_type: SigHashType,
+    ) -> Result<SigHash, encode::Error> {
         let mut enc = SigHash::engine();
-        self.encode_signing_data_to(&mut enc, input_index, script_code, value, sighash_type)
-            .expect("engines don't error");
-        SigHash::from_engine(enc)
+        self.encode_signing_data_to(&mut enc, input_index, script_code, value, sighash_type)?;
+        Ok(SigHash::from_engine(enc))
+    }
+
+    /// Compute the sighash exactly like elementsd does, committing to the consensus encoding of
+    /// the spent output's `value`.
+    ///
+    /// `value` is either an [`ExplicitValue`] or a [`ValueCommitment`]. Unlike
+    /// [`signature_hash`], which commits to a plain little-endian amount like Bitcoin, this is
+    /// what Elements nodes verify signatures against.
+    ///
+    /// [`ExplicitValue`]: crate::ExplicitValue
+    /// [`signature_hash`]: SigHashCache::signature_hash
+    pub fn signature_hash_elements<V: Encodable>(
+        &mut self,
+        input_index: usize,
+        script_code: &Script,
+        value: &V,
+        sighash_type: SigHashType,
+    ) -> Result<SigHash, encode::Error> {
+        let mut enc = SigHash::engine();
+        self.encode_signing_data_with_amount_to(
+            &mut enc,
+            input_index,
+            script_code,
+            &encode::serialize(value),
+            sighash_type,
+        )?;
+        Ok(SigHash::from_engine(enc))
+    }
+
+    /// Whether `signature` is a valid signature of `pubkey` for the input at `input_index`, like
+    /// elementsd checks it.
+    ///
+    /// `signature` is a DER-encoded signature followed by the sighash byte, as found in a
+    /// witness, and the sighash byte has to match `sighash_type`. The sighash is computed as in
+    /// [`signature_hash_elements`]. Like standardness rules demand, the signature has to be
+    /// strictly DER-encoded and have a low S value.
+    ///
+    /// Also returns `false` if the sighash cannot be computed for `input_index`.
+    ///
+    /// [`signature_hash_elements`]: SigHashCache::signature_hash_elements
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_signature<C: Verification, V: Encodable>(
+        &mut self,
+        secp: &Secp256k1<C>,
+        input_index: usize,
+        script_code: &Script,
+        value: &V,
+        sighash_type: SigHashType,
+        signature: &[u8],
+        pubkey: &PublicKey,
+    ) -> bool {
+        let (sighash_byte, der) = match signature.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
+        if u32::from(*sighash_byte) != sighash_type.as_u32() {
+            return false;
+        }
+
+        // `from_der` only accepts strict DER
+        let signature = match Signature::from_der(der) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+        let mut normalized = signature;
+        normalized.normalize_s();
+        if normalized != signature {
+            return false;
+        }
+
+        let sighash =
+            match self.signature_hash_elements(input_index, script_code, value, sighash_type) {
+                Ok(sighash) => sighash,
+                Err(_) => return false,
+            };
+        let message = Message::from_slice(&sighash[..]).expect("sighash is 32 bytes");
+
+        secp.verify(&message, &signature, pubkey).is_ok()
+    }
+
+    /// Compute the full BIP143 signing data, i.e. the preimage of [`signature_hash`].
+    ///
+    /// Covenants using `OP_CHECKSIGFROMSTACK` hash this message themselves.
+    ///
+    /// [`signature_hash`]: SigHashCache::signature_hash
+    pub fn signing_preimage(
+        &mut self,
+        input_index: usize,
+        script_code: &Script,
+        value: u64,
+        sighash_type: SigHashType,
+    ) -> Result<Vec<u8>, encode::Error> {
+        let mut preimage = Vec::new();
+        self.encode_signing_data_to(&mut preimage, input_index, script_code, value, sighash_type)?;
+        Ok(preimage)
+    }
+
+    /// Compute the BIP143 sighash for each of the standard sighash types.
+    ///
+    /// This is a diagnostic tool: comparing the message of a failing signature against all of
+    /// these reveals whether it was created with an unexpected sighash type.
+    pub fn signature_hashes_all_modes(
+        &mut self,
+        input_index: usize,
+        script_code: &Script,
+        value: u64,
+    ) -> Result<Vec<(SigHashType, SigHash)>, encode::Error> {
+        [
+            SigHashType::ALL,
+            SigHashType::NONE,
+            SigHashType::SINGLE,
+            SigHashType::ALL_PLUS_ANYONECANPAY,
+            SigHashType::NONE_PLUS_ANYONECANPAY,
+            SigHashType::SINGLE_PLUS_ANYONECANPAY,
+        ]
+        .iter()
+        .map(|&sighash_type| {
+            let sighash = self.try_signature_hash(input_index, script_code, value, sighash_type)?;
+            Ok((sighash_type, sighash))
+        })
+        .collect()
+    }
+
+    /// Compute the sighash of each of the given inputs, yielding them together with their input
+    /// index.
+    ///
+    /// The transaction-wide hashes are shared between all inputs. Each item fails like
+    /// [`encode_signing_data_with_amount_to`] does for the corresponding context.
+    ///
+    /// [`encode_signing_data_with_amount_to`]: SigHashCache::encode_signing_data_with_amount_to
+    pub fn signature_hashes<'a>(
+        &'a mut self,
+        contexts: &'a [SigningContext],
+    ) -> impl Iterator<Item = Result<(usize, SigHash), encode::Error>> + 'a {
+        contexts.iter().map(move |context| {
+            let mut enc = SigHash::engine();
+            self.encode_signing_data_with_amount_to(
+                &mut enc,
+                context.input_index,
+                &context.script_code,
+                &context.amount,
+                context.sighash_type,
+            )?;
+            Ok((context.input_index, SigHash::from_engine(enc)))
+        })
+    }
+}
+
+/// Errors that can occur when signing a transaction input.
+#[derive(Debug)]
+pub enum SignError {
+    /// The transaction has no input at the given index.
+    InputIndexOutOfRange(usize),
+    /// The spent output is a null output and cannot be signed for.
+    NullPrevout,
+    /// The spent output's scriptPubKey is not the one derived from the signing key.
+    ScriptPubkeyMismatch,
+    /// The witness script is not a standard `m-of-n` multisig script.
+    NotMultisig,
+    /// Fewer of the given keys than required match the pubkeys of the multisig script.
+    NotEnoughKeys {
+        /// The number of signatures required by the script
+        required: usize,
+        /// The number of given keys matching the script
+        found: usize,
+    },
+}
+
+impl fmt::Display for SignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignError::InputIndexOutOfRange(index) => {
+                write!(f, "transaction has no input at index {}", index)
+            }
+            SignError::NullPrevout => write!(f, "cannot sign for a null output"),
+            SignError::ScriptPubkeyMismatch => write!(
+                f,
+                "scriptPubKey of spent output does not match the signing key"
+            ),
+            SignError::NotMultisig => write!(f, "witness script is not a multisig script"),
+            SignError::NotEnoughKeys { required, found } => write!(
+                f,
+                "multisig script requires {} signatures but only {} keys match",
+                required, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SignError {}
+
+/// Sign the P2WPKH input at `input_index` of `tx` which spends `prevout` and insert the
+/// resulting witness `[signature + sighash type, pubkey]` into the input.
+///
+/// The script code, the sighash and the (low-S) signature are all derived from `sk`.
+pub fn sign_p2wpkh<C: Signing>(
+    secp: &Secp256k1<C>,
+    tx: &mut Transaction,
+    input_index: usize,
+    prevout: &TxOut,
+    sk: &SecretKey,
+    sighash_type: SigHashType,
+) -> Result<(), SignError> {
+    if input_index >= tx.input.len() {
+        return Err(SignError::InputIndexOutOfRange(input_index));
+    }
+
+    let pk = bitcoin::PublicKey {
+        compressed: true,
+        key: PublicKey::from_secret_key(secp, sk),
+    };
+
+    let wpkh = WPubkeyHash::hash(&pk.to_bytes());
+    if prevout.script_pubkey() != &Script::new_v0_wpkh(&wpkh) {
+        return Err(SignError::ScriptPubkeyMismatch);
+    }
+
+    let script_code = Script::new_p2pkh(&PubkeyHash::hash(&pk.to_bytes()));
+    let signature = match prevout {
+        TxOut::Explicit(explicit) => sign_input(
+            secp,
+            tx,
+            input_index,
+            &script_code,
+            &explicit.value,
+            sk,
+            sighash_type,
+        ),
+        TxOut::Confidential(confidential) => sign_input(
+            secp,
+            tx,
+            input_index,
+            &script_code,
+            &confidential.value,
+            sk,
+            sighash_type,
+        ),
+        TxOut::Null(_) => return Err(SignError::NullPrevout),
+    };
+
+    tx.input[input_index].witness.script_witness = vec![signature, pk.to_bytes()];
+
+    Ok(())
+}
+
+/// Sign the P2WSH input at `input_index` of `tx` whose `witness_script` is an `m-of-n` multisig
+/// script and insert the resulting witness `OP_0 <sig1> ... <sigm> <witness_script>`.
+///
+/// `value` is the (explicit or committed) value of the spent output. Signatures are produced
+/// in the order in which the corresponding pubkeys appear in the script, and signing stops once
+/// `m` signatures have been collected.
+pub fn sign_p2wsh_multisig<C: Signing, V: Encodable>(
+    secp: &Secp256k1<C>,
+    tx: &mut Transaction,
+    input_index: usize,
+    witness_script: &Script,
+    value: &V,
+    sks: &[SecretKey],
+    sighash_type: SigHashType,
+) -> Result<(), SignError> {
+    if input_index >= tx.input.len() {
+        return Err(SignError::InputIndexOutOfRange(input_index));
+    }
+
+    let (required, pubkeys) = witness_script
+        .parse_multisig()
+        .ok_or(SignError::NotMultisig)?;
+    let pubkeys = pubkeys
+        .into_iter()
+        .map(PublicKey::from_slice)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| SignError::NotMultisig)?;
+
+    let mut signatures = Vec::with_capacity(required);
+    for pubkey in pubkeys {
+        if signatures.len() == required {
+            break;
+        }
+
+        if let Some(sk) = sks
+            .iter()
+            .find(|sk| PublicKey::from_secret_key(secp, sk) == pubkey)
+        {
+            signatures.push(sign_input(
+                secp,
+                tx,
+                input_index,
+                witness_script,
+                value,
+                sk,
+                sighash_type,
+            ));
+        }
+    }
+
+    if signatures.len() < required {
+        return Err(SignError::NotEnoughKeys {
+            required,
+            found: signatures.len(),
+        });
+    }
+
+    let mut witness = Vec::with_capacity(required + 2);
+    witness.push(vec![]);
+    witness.extend(signatures);
+    witness.push(witness_script.to_bytes());
+
+    tx.input[input_index].witness.script_witness = witness;
+
+    Ok(())
+}
+
+/// Encode the rangeproof and the surjection proof of `txout` like elementsd does for
+/// `SIGHASH_RANGEPROOF`, i.e. as empty proofs for outputs without a witness.
+fn encode_proofs<W: io::Write>(txout: &TxOut, mut writer: W) -> Result<(), encode::Error> {
+    match txout.as_confidential() {
+        Some(confidential) => {
+            confidential
+                .witness
+                .rangeproof
+                .consensus_encode(&mut writer)?;
+            confidential
+                .witness
+                .surjection_proof
+                .consensus_encode(&mut writer)?;
+        }
+        None => {
+            0u8.consensus_encode(&mut writer)?;
+            0u8.consensus_encode(&mut writer)?;
+        }
+    }
+    Ok(())
+}
+
+/// Compute the sighash of an input and sign it, returning the DER-encoded signature with the
+/// sighash type appended.
+fn sign_input<C: Signing, V: Encodable>(
+    secp: &Secp256k1<C>,
+    tx: &Transaction,
+    input_index: usize,
+    script_code: &Script,
+    value: &V,
+    sk: &SecretKey,
+    sighash_type: SigHashType,
+) -> Vec<u8> {
+    let digest = tx_get_elements_signature_hash(
+        tx,
+        input_index,
+        script_code,
+        value,
+        sighash_type.as_u32(),
+        true,
+    );
+
+    let message = Message::from_slice(&digest.into_inner()).expect("sighash is 32 bytes");
+    let sig = secp.sign(&message, sk);
+
+    let mut serialized_signature = sig.serialize_der().to_vec();
+    serialized_signature.push(sighash_type.as_u32() as u8);
+
+    serialized_signature
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        opcodes, script::Builder, Address, AddressParams, AssetId, ExplicitValue, OutPoint, TxIn,
+    };
+
+    /// A transaction spending `prevout` with a single input, paying 99_000 of its asset back to
+    /// the same script pubkey and a fee of 1_000.
+    fn spend_tx(prevout: &TxOut) -> Transaction {
+        let asset = prevout.explicit_asset().expect("explicit prevout");
+
+        Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Default::default(), 0),
+                is_pegin: false,
+                has_issuance: false,
+                script_sig: Script::new(),
+                sequence: 0xFFFF_FFFF,
+                asset_issuance: Default::default(),
+                witness: Default::default(),
+            }],
+            output: vec![
+                TxOut::new_explicit(asset, 99_000, prevout.script_pubkey().clone()),
+                TxOut::new_fee(asset, 1_000),
+            ],
+        }
+    }
+
+    /// A transaction with `inputs` copies of the input of [`spend_tx`], spending an explicit
+    /// output of 100_000, and the p2pkh script code to sign them with.
+    fn spend_fixture(inputs: usize) -> (Transaction, Script) {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let prevout = TxOut::new_explicit(asset, 100_000, Script::new());
+        let mut tx = spend_tx(&prevout);
+        let input = tx.input[0].clone();
+        tx.input = vec![input; inputs];
+
+        (tx, Script::new_p2pkh(&Default::default()))
+    }
+
+    /// A key and an explicit output of 100_000 paying to its p2wpkh address.
+    fn p2wpkh_prevout<C: Signing>(secp: &Secp256k1<C>) -> (SecretKey, bitcoin::PublicKey, TxOut) {
+        let sk = SecretKey::from_slice(&[0xcd; 32]).unwrap();
+        let pk = bitcoin::PublicKey {
+            compressed: true,
+            key: PublicKey::from_secret_key(secp, &sk),
+        };
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let address = Address::p2wpkh(&pk, None, &AddressParams::ELEMENTS);
+        let prevout = TxOut::new_explicit(asset, 100_000, address.script_pubkey());
+
+        (sk, pk, prevout)
+    }
+
+    #[test]
+    fn components() {
+        let tx: Transaction = hex_deserialize!(
+            "020000000001eb04b68e9a26d116046c76e8ff47332fb71dda90ff4bef5370f2\
+             5226d3bc09fc0000000000feffffff0201230f4f5d4b7c6fa845806ee4f67713\
+             459e1b69e8e60fcee2e4940c7a0d5de1b20100000002540bd71c001976a91448\
+             633e2c0ee9495dd3f9c43732c47f4702a362c888ac01230f4f5d4b7c6fa84580\
+             6ee4f67713459e1b69e8e60fcee2e4940c7a0d5de1b2010000000000000ce400\
+             0000000000"
+        );
+
+        let components = SigHashCache::new(&tx).components();
+
+        assert_eq!(
+            hex::encode(components.hash_prevouts.into_inner()),
+            "cc9a8eb7f644b0a2ed6717e47100e4970a2a2422ea0c9e761e46374a2ce98135"
+        );
+        assert_eq!(
+            hex::encode(components.hash_sequence.into_inner()),
+            "18606b350cd8bf565266bc352f0caddcf01e8fa789dd8a15386327cf8cabe198"
+        );
+        assert_eq!(
+            hex::encode(components.hash_issuances.into_inner()),
+            "1406e05881e299367766d313e26c05564ec91bf721d31726bd6e46e60689539a"
+        );
+        assert_eq!(
+            hex::encode(components.hash_outputs.into_inner()),
+            "553da5335c35f14a3ddc96891b3ce9f2e39f0bba143185d5004c8656db262f34"
+        );
+    }
+
+    #[test]
+    fn output_hash_without_outputs() {
+        let (mut tx, script_code) = spend_fixture(1);
+        tx.output.clear();
+
+        let output_hash = |sighash_type| {
+            let data = SigHashCache::new(&tx)
+                .signing_preimage(0, &script_code, 100_000, sighash_type)
+                .unwrap();
+            sha256d::Hash::from_slice(&data[data.len() - 40..data.len() - 8]).unwrap()
+        };
+
+        let empty_hash = sha256d::Hash::hash(&[]);
+        assert_eq!(SigHashCache::new(&tx).hash_outputs(), empty_hash);
+        assert_eq!(output_hash(SigHashType::ALL), empty_hash);
+        assert_eq!(output_hash(SigHashType::NONE), sha256d::Hash::default());
+        assert_eq!(output_hash(SigHashType::SINGLE), sha256d::Hash::default());
+    }
+
+    #[test]
+    fn input_index_out_of_range() {
+        let (tx, script_code) = spend_fixture(1);
+        let mut cache = SigHashCache::new(&tx);
+
+        let mut data = Vec::new();
+        assert!(cache
+            .encode_signing_data_to(&mut data, 1, &script_code, 100_000, SigHashType::ALL)
+            .is_err());
+        assert!(data.is_empty());
+        assert!(cache
+            .try_signature_hash(1, &script_code, 100_000, SigHashType::ALL)
+            .is_err());
+        assert!(cache
+            .signing_preimage(1, &script_code, 100_000, SigHashType::ALL)
+            .is_err());
+        assert!(cache
+            .signature_hashes_all_modes(1, &script_code, 100_000)
+            .is_err());
+    }
+
+    #[test]
+    fn current_input_fields() {
+        let (mut tx, _) = spend_fixture(1);
+        let mut input = tx.input[0].clone();
+        input.previous_output.vout = 1;
+        input.sequence = 0xFFFF_FFFE;
+        tx.input.push(input);
+        let cache = SigHashCache::new(&tx);
+
+        assert_eq!(
+            cache.current_input_fields(0),
+            Some((&OutPoint::new(Default::default(), 0), 0xFFFF_FFFF))
+        );
+        assert_eq!(
+            cache.current_input_fields(1),
+            Some((&OutPoint::new(Default::default(), 1), 0xFFFF_FFFE))
+        );
+        assert_eq!(cache.current_input_fields(2), None);
+    }
+
+    #[test]
+    fn signing_data_with_verbatim_amount() {
+        let (tx, script_code) = spend_fixture(1);
+        let mut cache = SigHashCache::new(&tx);
+
+        let encode = |cache: &mut SigHashCache<&Transaction>, amount: &[u8]| {
+            let mut data = Vec::new();
+            cache
+                .encode_signing_data_with_amount_to(
+                    &mut data,
+                    0,
+                    &script_code,
+                    amount,
+                    SigHashType::ALL,
+                )
+                .map(|_| data)
+        };
+
+        assert_eq!(
+            encode(&mut cache, &100_000u64.to_le_bytes()).unwrap(),
+            cache
+                .signing_preimage(0, &script_code, 100_000, SigHashType::ALL)
+                .unwrap()
+        );
+
+        let commitment = ValueCommitment::from_commitment(0x08, &[2; 32]).unwrap();
+        let with_commitment = encode(&mut cache, &commitment.commitment()).unwrap();
+        // the amount is followed by the sequence, the output hash, the lock time and the
+        // sighash type
+        let end = with_commitment.len() - 44;
+        assert_eq!(with_commitment[end - 33..end], commitment.commitment()[..]);
+
+        assert!(encode(&mut cache, &[]).is_err());
+        assert!(encode(&mut cache, &[0x02; 9]).is_err());
+    }
+
+    #[test]
+    fn signature_hash_elements_matches_libwally() {
+        let (tx, script_code) = spend_fixture(1);
+        let mut cache = SigHashCache::new(&tx);
+
+        let explicit = ExplicitValue(100_000);
+        let commitment = ValueCommitment::from_commitment(0x09, &[2; 32]).unwrap();
+
+        for &sighash_type in &[SigHashType::ALL, SigHashType::SINGLE_PLUS_ANYONECANPAY] {
+            assert_eq!(
+                cache
+                    .signature_hash_elements(0, &script_code, &explicit, sighash_type)
+                    .unwrap()
+                    .as_hash(),
+                tx_get_elements_signature_hash(
+                    &tx,
+                    0,
+                    &script_code,
+                    &explicit,
+                    sighash_type.as_u32(),
+                    true
+                )
+            );
+            assert_eq!(
+                cache
+                    .signature_hash_elements(0, &script_code, &commitment, sighash_type)
+                    .unwrap()
+                    .as_hash(),
+                tx_get_elements_signature_hash(
+                    &tx,
+                    0,
+                    &script_code,
+                    &commitment,
+                    sighash_type.as_u32(),
+                    true
+                )
+            );
+        }
+
+        assert!(cache
+            .signature_hash_elements(1, &script_code, &explicit, SigHashType::ALL)
+            .is_err());
+    }
+
+    #[test]
+    fn signature_hashes_of_contexts() {
+        let (tx, script_code) = spend_fixture(2);
+        let commitment = ValueCommitment::from_commitment(0x09, &[2; 32]).unwrap();
+
+        let contexts = vec![
+            SigningContext::explicit(0, script_code.clone(), 100_000, SigHashType::ALL),
+            SigningContext::confidential(1, script_code.clone(), commitment, SigHashType::SINGLE),
+        ];
+        let mut cache = SigHashCache::new(&tx);
+        let sighashes = cache
+            .signature_hashes(&contexts)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let mut expected = SigHash::engine();
+        SigHashCache::new(&tx)
+            .encode_signing_data_with_amount_to(
+                &mut expected,
+                1,
+                &script_code,
+                &commitment.commitment(),
+                SigHashType::SINGLE,
+            )
+            .unwrap();
+        assert_eq!(
+            sighashes,
+            vec![
+                (
+                    0,
+                    SigHashCache::new(&tx).signature_hash(
+                        0,
+                        &script_code,
+                        100_000,
+                        SigHashType::ALL
+                    )
+                ),
+                (1, SigHash::from_engine(expected)),
+            ]
+        );
+
+        let out_of_range = [SigningContext::explicit(
+            2,
+            script_code,
+            100_000,
+            SigHashType::ALL,
+        )];
+        assert!(cache
+            .signature_hashes(&out_of_range)
+            .next()
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn rangeproof_flag_commits_to_output_proofs() {
+        let (tx, script_code) = spend_fixture(1);
+        let mut cache = SigHashCache::new(&tx);
+        let sighash_rangeproof = SigHashType::from_u32(0x41);
+
+        assert_ne!(
+            cache.signature_hash(0, &script_code, 100_000, sighash_rangeproof),
+            cache.signature_hash(0, &script_code, 100_000, SigHashType::ALL)
+        );
+
+        let preimage_all = cache
+            .signing_preimage(0, &script_code, 100_000, SigHashType::ALL)
+            .unwrap();
+        let preimage_rangeproof = cache
+            .signing_preimage(0, &script_code, 100_000, sighash_rangeproof)
+            .unwrap();
+
+        // hashRangeproofs goes between hashOutputs and the locktime
+        let split = preimage_all.len() - 8;
+        assert_eq!(preimage_rangeproof.len(), preimage_all.len() + 32);
+        assert_eq!(preimage_rangeproof[..split], preimage_all[..split]);
+        assert_eq!(
+            preimage_rangeproof[split..split + 32],
+            cache.hash_rangeproofs()[..]
+        );
+        assert_eq!(
+            preimage_rangeproof[split + 32..split + 36],
+            preimage_all[split..split + 4]
+        );
+        assert_eq!(preimage_rangeproof[split + 36..], 0x41u32.to_le_bytes());
+    }
+
+    #[test]
+    fn signing_preimage_hashes_to_sighash() {
+        let (tx, script_code) = spend_fixture(1);
+
+        let mut cache = SigHashCache::new(&tx);
+        let preimage = cache
+            .signing_preimage(0, &script_code, 100_000, SigHashType::ALL)
+            .unwrap();
+
+        assert_eq!(
+            SigHash::hash(&preimage),
+            cache.signature_hash(0, &script_code, 100_000, SigHashType::ALL)
+        );
+        assert_eq!(preimage[..4], tx.version.to_le_bytes());
+        assert_eq!(
+            preimage[preimage.len() - 4..],
+            SigHashType::ALL.as_u32().to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn signature_hashes_all_modes_match_individual_sighashes() {
+        let (tx, script_code) = spend_fixture(1);
+
+        let mut cache = SigHashCache::new(&tx);
+        let all_modes = cache
+            .signature_hashes_all_modes(0, &script_code, 100_000)
+            .unwrap();
+
+        assert_eq!(all_modes.len(), 6);
+        for (sighash_type, sighash) in &all_modes {
+            assert_eq!(
+                *sighash,
+                cache.signature_hash(0, &script_code, 100_000, *sighash_type)
+            );
+        }
+        for (i, (_, a)) in all_modes.iter().enumerate() {
+            for (_, b) in &all_modes[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn sighash_none_and_single_only_commit_to_own_sequence() {
+        let (tx, script_code) = spend_fixture(2);
+
+        // the sequence hash follows the version and the prevouts hash, the input's own sequence
+        // is followed by the output hash, the lock time and the sighash type
+        let preimage = |tx: &Transaction, sighash_type| {
+            SigHashCache::new(tx)
+                .signing_preimage(0, &script_code, 100_000, sighash_type)
+                .unwrap()
+        };
+
+        for &sighash_type in &[SigHashType::NONE, SigHashType::SINGLE] {
+            let original = preimage(&tx, sighash_type);
+            assert_eq!(original[36..68], [0; 32]);
+            assert_eq!(
+                original[original.len() - 44..original.len() - 40],
+                0xFFFF_FFFFu32.to_le_bytes()
+            );
+
+            let mut other_sequence = tx.clone();
+            other_sequence.input[1].sequence = 0;
+            assert_eq!(preimage(&other_sequence, sighash_type), original);
+
+            let mut own_sequence = tx.clone();
+            own_sequence.input[0].sequence = 0;
+            assert_ne!(preimage(&own_sequence, sighash_type), original);
+        }
+
+        let mut other_sequence = tx.clone();
+        other_sequence.input[1].sequence = 0;
+        assert_ne!(
+            preimage(&other_sequence, SigHashType::ALL),
+            preimage(&tx, SigHashType::ALL)
+        );
+    }
+
+    #[test]
+    fn sighash_single_commits_to_output_at_input_index_or_zero_hash() {
+        let (tx, script_code) = spend_fixture(4);
+
+        // the output hash is followed by the lock time and the sighash type
+        let single_output_hash = |input_index| {
+            let mut data = Vec::new();
+            SigHashCache::new(&tx)
+                .encode_signing_data_to(
+                    &mut data,
+                    input_index,
+                    &script_code,
+                    100_000,
+                    SigHashType::SINGLE,
+                )
+                .unwrap();
+            SigHash::from_slice(&data[data.len() - 40..data.len() - 8]).unwrap()
+        };
+        let output_hash = |output| {
+            let mut enc = SigHash::engine();
+            TxOut::consensus_encode(output, &mut enc).unwrap();
+            SigHash::from_engine(enc)
+        };
+
+        assert_eq!(tx.output.len(), 2);
+        assert_eq!(single_output_hash(0), output_hash(&tx.output[0]));
+        assert_eq!(single_output_hash(1), output_hash(&tx.output[1]));
+        assert_eq!(single_output_hash(2), SigHash::default());
+        assert_eq!(single_output_hash(3), SigHash::default());
+    }
+
+    #[test]
+    fn sign_p2wpkh_produces_valid_witness() {
+        let secp = Secp256k1::new();
+        let (sk, pk, prevout) = p2wpkh_prevout(&secp);
+
+        let mut tx = spend_tx(&prevout);
+        sign_p2wpkh(&secp, &mut tx, 0, &prevout, &sk, SigHashType::ALL).unwrap();
+
+        let witness = &tx.input[0].witness.script_witness;
+        assert_eq!(witness.len(), 2);
+        assert_eq!(witness[1], pk.to_bytes());
+
+        let (sighash_byte, der) = witness[0].split_last().unwrap();
+        assert_eq!(*sighash_byte, SigHashType::ALL.as_u32() as u8);
+
+        let script_code = Script::new_p2pkh(&PubkeyHash::hash(&pk.to_bytes()));
+        let digest = tx_get_elements_signature_hash(
+            &tx,
+            0,
+            &script_code,
+            &prevout.as_explicit().unwrap().value,
+            SigHashType::ALL.as_u32(),
+            true,
+        );
+        let message = Message::from_slice(&digest.into_inner()).unwrap();
+        let sig = Signature::from_der(der).unwrap();
+        assert!(secp.verify(&message, &sig, &pk.key).is_ok());
+    }
+
+    #[test]
+    fn verify_signature() {
+        let secp = Secp256k1::new();
+        let (sk, pk, prevout) = p2wpkh_prevout(&secp);
+        let value = &prevout.as_explicit().unwrap().value;
+        let script_code = Script::new_p2pkh(&PubkeyHash::hash(&pk.to_bytes()));
+
+        let mut tx = spend_tx(&prevout);
+        sign_p2wpkh(&secp, &mut tx, 0, &prevout, &sk, SigHashType::ALL).unwrap();
+        let signature = tx.input[0].witness.script_witness[0].clone();
+
+        let mut cache = SigHashCache::new(&tx);
+        let mut verify = |signature: &[u8], sighash_type, pubkey| {
+            cache.verify_signature(
+                &secp,
+                0,
+                &script_code,
+                value,
+                sighash_type,
+                signature,
+                pubkey,
+            )
+        };
+        assert!(verify(&signature, SigHashType::ALL, &pk.key));
+
+        let other_pk = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[1; 32]).unwrap());
+        assert!(!verify(&signature, SigHashType::ALL, &other_pk));
+        assert!(!verify(&signature, SigHashType::NONE, &pk.key));
+        assert!(!verify(&[], SigHashType::ALL, &pk.key));
+
+        let (sighash_byte, der) = signature.split_last().unwrap();
+
+        // the same signature with the high S value n - s
+        let mut compact = Signature::from_der(der).unwrap().serialize_compact();
+        let order = bitcoin::secp256k1::constants::CURVE_ORDER;
+        let mut borrow = 0;
+        for i in (32..64).rev() {
+            let difference = i16::from(order[i - 32]) - i16::from(compact[i]) - borrow;
+            borrow = if difference < 0 { 1 } else { 0 };
+            compact[i] = (difference + 256 * borrow) as u8;
+        }
+        let mut high_s = Signature::from_compact(&compact)
+            .unwrap()
+            .serialize_der()
+            .to_vec();
+        high_s.push(*sighash_byte);
+        assert!(!verify(&high_s, SigHashType::ALL, &pk.key));
+
+        // the same signature with a non-minimal length of the sequence
+        let mut non_strict = vec![0x30, 0x81];
+        non_strict.extend_from_slice(&der[1..]);
+        non_strict.push(*sighash_byte);
+        assert!(!verify(&non_strict, SigHashType::ALL, &pk.key));
+
+        let mut cache = SigHashCache::new(&tx);
+        assert!(!cache.verify_signature(
+            &secp,
+            1,
+            &script_code,
+            value,
+            SigHashType::ALL,
+            &signature,
+            &pk.key
+        ));
+    }
+
+    #[test]
+    fn sign_p2wpkh_rejects_foreign_prevout() {
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[0xcd; 32]).unwrap();
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let prevout = TxOut::new_explicit(asset, 100_000, Script::new_v0_wpkh(&Default::default()));
+
+        let mut tx = spend_tx(&prevout);
+
+        assert!(matches!(
+            sign_p2wpkh(&secp, &mut tx, 0, &prevout, &sk, SigHashType::ALL),
+            Err(SignError::ScriptPubkeyMismatch)
+        ));
+        assert!(matches!(
+            sign_p2wpkh(&secp, &mut tx, 1, &prevout, &sk, SigHashType::ALL),
+            Err(SignError::InputIndexOutOfRange(1))
+        ));
+    }
+
+    #[test]
+    fn sign_p2wsh_multisig_collects_signatures_in_script_order() {
+        let secp = Secp256k1::new();
+        let sks = [
+            SecretKey::from_slice(&[0x01; 32]).unwrap(),
+            SecretKey::from_slice(&[0x02; 32]).unwrap(),
+            SecretKey::from_slice(&[0x03; 32]).unwrap(),
+        ];
+        let pks = sks
+            .iter()
+            .map(|sk| bitcoin::PublicKey {
+                compressed: true,
+                key: PublicKey::from_secret_key(&secp, sk),
+            })
+            .collect::<Vec<_>>();
+        let witness_script = Builder::new()
+            .push_int(2)
+            .push_key(&pks[0])
+            .push_key(&pks[1])
+            .push_key(&pks[2])
+            .push_int(3)
+            .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+            .into_script();
+
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let prevout = TxOut::new_explicit(asset, 100_000, witness_script.to_v0_p2wsh());
+        let value = prevout.as_explicit().unwrap().value;
+        let mut tx = spend_tx(&prevout);
+
+        // keys are given out of order and one more than required
+        sign_p2wsh_multisig(
+            &secp,
+            &mut tx,
+            0,
+            &witness_script,
+            &value,
+            &[sks[2], sks[0], sks[1]],
+            SigHashType::ALL,
+        )
+        .unwrap();
+
+        let witness = &tx.input[0].witness.script_witness;
+        assert_eq!(witness.len(), 4);
+        assert!(witness[0].is_empty());
+        assert_eq!(witness[3], witness_script.to_bytes());
+
+        let digest = tx_get_elements_signature_hash(
+            &tx,
+            0,
+            &witness_script,
+            &value,
+            SigHashType::ALL.as_u32(),
+            true,
+        );
+        let message = Message::from_slice(&digest.into_inner()).unwrap();
+        for (sig, pk) in witness[1..3].iter().zip(&pks[0..2]) {
+            let (_, der) = sig.split_last().unwrap();
+            let sig = Signature::from_der(der).unwrap();
+            assert!(secp.verify(&message, &sig, &pk.key).is_ok());
+        }
+    }
+
+    #[test]
+    fn sign_p2wsh_multisig_requires_enough_keys() {
+        let secp = Secp256k1::new();
+        let sks = [
+            SecretKey::from_slice(&[0x01; 32]).unwrap(),
+            SecretKey::from_slice(&[0x02; 32]).unwrap(),
+        ];
+        let witness_script = sks
+            .iter()
+            .fold(Builder::new().push_int(2), |builder, sk| {
+                builder.push_key(&bitcoin::PublicKey {
+                    compressed: true,
+                    key: PublicKey::from_secret_key(&secp, sk),
+                })
+            })
+            .push_int(2)
+            .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+            .into_script();
+
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let prevout = TxOut::new_explicit(asset, 100_000, witness_script.to_v0_p2wsh());
+        let value = prevout.as_explicit().unwrap().value;
+        let mut tx = spend_tx(&prevout);
+
+        assert!(matches!(
+            sign_p2wsh_multisig(
+                &secp,
+                &mut tx,
+                0,
+                &witness_script,
+                &value,
+                &[sks[1]],
+                SigHashType::ALL,
+            ),
+            Err(SignError::NotEnoughKeys {
+                required: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            sign_p2wsh_multisig(
+                &secp,
+                &mut tx,
+                0,
+                &prevout.script_pubkey().clone(),
+                &value,
+                &sks,
+                SigHashType::ALL,
+            ),
+            Err(SignError::NotMultisig)
+        ));
     }
 }
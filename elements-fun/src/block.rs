@@ -431,6 +431,15 @@ mod tests {
         assert_eq!(block.get_size(), serialize(&block).len());
         assert_eq!(block.get_weight(), 1089);
 
+        // the transaction count is subject to the allocation limit
+        let mut oversized = serialize(&block.header);
+        let tx_count = encode::MAX_VEC_SIZE / std::mem::size_of::<Transaction>() + 1;
+        oversized.extend(serialize(&VarInt(tx_count as u64)));
+        assert!(matches!(
+            encode::deserialize::<Block>(&oversized),
+            Err(encode::Error::OversizedVectorAllocation { .. })
+        ));
+
         // Block with 3 transactions ... the rangeproofs are very large :)
         let block: Block = hex_deserialize!(
             "000000207e3dba98460e4136659f0fccf3e59338dfe53ed5f094fb0bb94d771c\
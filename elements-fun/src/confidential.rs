@@ -22,23 +22,37 @@ use crate::{
     wally::{asset_final_vbf, asset_generator_from_bytes, asset_value_commitment},
     AssetId,
 };
-use bitcoin::secp256k1::{
-    rand::{CryptoRng, Rng, RngCore},
-    PublicKey, Secp256k1, SecretKey, Signing,
+use bitcoin::{
+    hashes::{sha256, Hash},
+    secp256k1::{
+        self,
+        ecdh::SharedSecret,
+        rand::{CryptoRng, Rng, RngCore},
+        PublicKey, Secp256k1, SecretKey, Signing,
+    },
 };
 use hex::{FromHex, FromHexError};
 use std::{fmt, io, iter};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "subtle")]
+use subtle::{Choice, ConstantTimeEq, CtOption};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 // Helper macro to implement various things for the various confidential
 // commitment types
 macro_rules! impl_confidential_commitment {
     ($name:ident, $prefixA:expr, $prefixB:expr) => {
         impl $name {
+            /// The prefix of a commitment to a point with an even y-coordinate.
+            pub const PREFIX_EVEN: u8 = $prefixA;
+            /// The prefix of a commitment to a point with an odd y-coordinate.
+            pub const PREFIX_ODD: u8 = $prefixB;
+
             pub const fn is_valid_prefix(tag: u8) -> bool {
-                tag == $prefixA || tag == $prefixB
+                tag == Self::PREFIX_EVEN || tag == Self::PREFIX_ODD
             }
 
             pub fn from_commitment(tag: u8, xcoor: &[u8]) -> Result<Self, encode::Error> {
@@ -59,13 +73,45 @@ macro_rules! impl_confidential_commitment {
             }
 
             pub fn from_slice(commitment: &[u8]) -> Result<$name, encode::Error> {
+                if commitment.len() != 33 {
+                    return Err(encode::Error::ParseFailed(
+                        "commitment must be 33 bytes long",
+                    ));
+                }
+
                 Self::from_commitment(commitment[0], &commitment[1..])
             }
 
+            /// Like [`from_slice`](Self::from_slice), but checks the prefix in constant time.
+            ///
+            /// Only the length of the slice is branched on, the prefix and the x-coordinate
+            /// are always copied in full.
+            #[cfg(feature = "subtle")]
+            pub fn from_slice_ct(commitment: &[u8]) -> CtOption<$name> {
+                let mut bytes = [0u8; 33];
+                let correct_length = commitment.len() == bytes.len();
+                if correct_length {
+                    bytes.copy_from_slice(commitment);
+                }
+
+                let valid_prefix =
+                    bytes[0].ct_eq(&Self::PREFIX_EVEN) | bytes[0].ct_eq(&Self::PREFIX_ODD);
+
+                CtOption::new(
+                    Self(bytes),
+                    Choice::from(correct_length as u8) & valid_prefix,
+                )
+            }
+
             pub fn commitment(&self) -> [u8; 33] {
                 self.0
             }
 
+            /// The prefix byte this commitment is serialized with.
+            pub fn prefix_byte(&self) -> u8 {
+                self.0[0]
+            }
+
             pub fn encoded_length(&self) -> usize {
                 33
             }
@@ -105,14 +151,22 @@ macro_rules! impl_confidential_commitment {
             }
         }
 
+        /// Hex in human-readable formats, otherwise the 33 bytes as a fixed-size tuple which
+        /// binary formats like bincode encode without a length prefix.
         #[cfg(feature = "serde")]
         impl Serialize for $name {
             fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-                use serde::ser::SerializeSeq;
+                use serde::ser::SerializeTuple;
 
-                let mut seq = s.serialize_seq(Some(33))?;
-                seq.serialize_element(self.0.as_ref())?;
-                seq.end()
+                if s.is_human_readable() {
+                    return s.collect_str(self);
+                }
+
+                let mut tuple = s.serialize_tuple(33)?;
+                for byte in self.0.iter() {
+                    tuple.serialize_element(byte)?;
+                }
+                tuple.end()
             }
         }
 
@@ -120,6 +174,12 @@ macro_rules! impl_confidential_commitment {
         impl<'de> Deserialize<'de> for $name {
             fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
                 use serde::de::{Error, SeqAccess, Visitor};
+
+                if d.is_human_readable() {
+                    let hex = String::deserialize(d)?;
+                    return $name::from_hex(&hex).map_err(D::Error::custom);
+                }
+
                 struct CommitVisitor;
 
                 impl<'de> Visitor<'de> for CommitVisitor {
@@ -133,30 +193,128 @@ macro_rules! impl_confidential_commitment {
                         self,
                         mut access: A,
                     ) -> Result<Self::Value, A::Error> {
-                        let prefix: u8 = if let Some(x) = access.next_element()? {
-                            x
-                        } else {
-                            return Err(A::Error::custom("missing prefix"));
-                        };
-
-                        if prefix != $prefixA && prefix != $prefixB {
-                            return Err(A::Error::custom(format!("invalid prefix {}", prefix)));
+                        let mut bytes = [0u8; 33];
+                        for (i, byte) in bytes.iter_mut().enumerate() {
+                            *byte = access
+                                .next_element()?
+                                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
                         }
 
-                        let xcoor = access
-                            .next_element::<[u8; 32]>()?
-                            .ok_or_else(|| A::Error::custom("missing x-coordinate"))?;
-
-                        Ok($name::from_commitment(prefix, &xcoor).map_err(A::Error::custom)?)
+                        $name::from_commitment(bytes[0], &bytes[1..]).map_err(A::Error::custom)
                     }
                 }
 
-                d.deserialize_seq(CommitVisitor)
+                d.deserialize_tuple(33, CommitVisitor)
+            }
+        }
+
+        #[cfg(feature = "base64")]
+        impl $name {
+            /// Encode the serialized commitment as base64.
+            pub fn to_base64(&self) -> String {
+                base64::encode(&self.0[..])
+            }
+
+            /// Decode a commitment from its base64-encoded serialization.
+            pub fn from_base64(s: &str) -> Result<Self, FromBase64Error> {
+                let bytes = base64::decode(s).map_err(FromBase64Error::Base64)?;
+
+                $name::from_slice(&bytes).map_err(FromBase64Error::Commitment)
+            }
+        }
+
+        #[cfg(feature = "base64")]
+        impl Base64Commitment for $name {
+            fn to_base64(&self) -> String {
+                $name::to_base64(self)
+            }
+
+            fn from_base64(s: &str) -> Result<Self, FromBase64Error> {
+                $name::from_base64(s)
             }
         }
     };
 }
 
+/// Commitments which can be encoded as base64.
+#[cfg(feature = "base64")]
+pub trait Base64Commitment: Sized {
+    fn to_base64(&self) -> String;
+    fn from_base64(s: &str) -> Result<Self, FromBase64Error>;
+}
+
+/// An error when decoding a commitment from base64.
+#[cfg(feature = "base64")]
+#[derive(Debug)]
+pub enum FromBase64Error {
+    /// The string is not valid base64
+    Base64(base64::DecodeError),
+    /// The decoded bytes are not a valid commitment
+    Commitment(encode::Error),
+}
+
+#[cfg(feature = "base64")]
+impl fmt::Display for FromBase64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromBase64Error::Base64(e) => write!(f, "invalid base64: {}", e),
+            FromBase64Error::Commitment(e) => write!(f, "invalid commitment: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "base64")]
+impl std::error::Error for FromBase64Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FromBase64Error::Base64(e) => Some(e),
+            FromBase64Error::Commitment(e) => Some(e),
+        }
+    }
+}
+
+/// Serialize commitments as base64 strings in human-readable formats, to be used with
+/// `#[serde(with = "elements_fun::confidential::serde_base64")]`.
+///
+/// Non-human-readable formats use the default serialization of the commitment.
+#[cfg(all(feature = "serde", feature = "base64"))]
+pub mod serde_base64 {
+    use super::Base64Commitment;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T, S>(commitment: &T, s: S) -> Result<S::Ok, S::Error>
+    where
+        T: Base64Commitment + Serialize,
+        S: Serializer,
+    {
+        if s.is_human_readable() {
+            s.serialize_str(&commitment.to_base64())
+        } else {
+            commitment.serialize(s)
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+    where
+        T: Base64Commitment + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        if d.is_human_readable() {
+            let s = String::deserialize(d)?;
+            T::from_base64(&s).map_err(D::Error::custom)
+        } else {
+            T::deserialize(d)
+        }
+    }
+}
+
+/// A blinded asset tag.
+///
+/// The serialization of an asset commitment is exactly the 33-byte serialization of a
+/// secp256k1-zkp generator: a prefix of `0x0a` or `0x0b` followed by the x-coordinate of the
+/// point `H(asset) + abf * G`. There is thus no separate generator type: an `AssetCommitment` is
+/// passed as-is wherever a generator is expected, e.g. as the base point of a
+/// [`ValueCommitment`].
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct AssetCommitment([u8; 33]);
 
@@ -164,6 +322,71 @@ impl AssetCommitment {
     pub fn new(asset: AssetId, bf: AssetBlindingFactor) -> Self {
         asset_generator_from_bytes(&asset, &bf)
     }
+
+    /// Whether this commitment blinds `asset` with the blinding factor `abf`.
+    ///
+    /// Lets the receiver of an output check the asset claimed by the sender before trusting
+    /// any unblinded value committed to with this generator.
+    pub fn verify(&self, asset: AssetId, abf: AssetBlindingFactor) -> bool {
+        AssetCommitment::new(asset, abf) == *self
+    }
+
+    /// Parse an asset commitment from a hex-encoded compressed point.
+    ///
+    /// Unlike [`from_hex`](FromHex::from_hex), this requires exactly 66 hex characters and
+    /// reports a compressed public key (prefix `0x02` or `0x03`) separately from other invalid
+    /// prefixes, so a public key is never ingested as an asset commitment by accident.
+    pub fn from_point_hex(hex: &str) -> Result<Self, FromPointHexError> {
+        if hex.len() != 66 {
+            return Err(FromPointHexError::InvalidLength(hex.len()));
+        }
+        let bytes = Vec::<u8>::from_hex(hex).map_err(FromPointHexError::Hex)?;
+
+        match bytes[0] {
+            prefix @ 0x02 | prefix @ 0x03 => Err(FromPointHexError::PublicKeyPrefix(prefix)),
+            _ => Self::from_slice(&bytes).map_err(FromPointHexError::Commitment),
+        }
+    }
+}
+
+/// An error when parsing a commitment from a hex-encoded compressed point.
+#[derive(Debug)]
+pub enum FromPointHexError {
+    /// The string is not 66 characters long
+    InvalidLength(usize),
+    /// The string is not valid hex
+    Hex(FromHexError),
+    /// The point is a compressed public key rather than a commitment
+    PublicKeyPrefix(u8),
+    /// The decoded bytes are not a valid commitment
+    Commitment(encode::Error),
+}
+
+impl fmt::Display for FromPointHexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromPointHexError::InvalidLength(len) => {
+                write!(f, "expected 66 hex characters, got {}", len)
+            }
+            FromPointHexError::Hex(e) => write!(f, "invalid hex: {}", e),
+            FromPointHexError::PublicKeyPrefix(prefix) => write!(
+                f,
+                "prefix 0x{:02x} denotes a public key, not a commitment",
+                prefix
+            ),
+            FromPointHexError::Commitment(e) => write!(f, "invalid commitment: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FromPointHexError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FromPointHexError::Hex(e) => Some(e),
+            FromPointHexError::Commitment(e) => Some(e),
+            FromPointHexError::InvalidLength(_) | FromPointHexError::PublicKeyPrefix(_) => None,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -175,10 +398,18 @@ impl ValueCommitment {
     }
 }
 
+/// The confidential nonce of an output, i.e. the sender's ephemeral ECDH public key.
+///
+/// Confidential nonces are prefixed with `0x02` or `0x03`, which are exactly the prefixes of
+/// compressed public keys. Any compressed public key therefore parses as a `NonceCommitment`, and
+/// [`from_slice`](Self::from_slice) cannot tell whether the bytes were meant as a nonce at all.
+///
+/// Explicit nonces (prefix `0x01`) are not representable by this type, outputs carry a [`Nonce`]
+/// to tell the two apart.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
-pub struct Nonce([u8; 33]);
+pub struct NonceCommitment([u8; 33]);
 
-impl Nonce {
+impl NonceCommitment {
     pub fn new<R: RngCore + CryptoRng, C: Signing>(
         rng: &mut R,
         secp: &Secp256k1<C>,
@@ -188,18 +419,150 @@ impl Nonce {
 
         (Self(commitment.serialize()), secret_key)
     }
+
+    /// The sender's ephemeral public key this nonce consists of, to perform ECDH with.
+    ///
+    /// Fails if the x-coordinate is not on the curve.
+    pub fn as_public_key(&self) -> Result<PublicKey, secp256k1::Error> {
+        PublicKey::from_slice(&self.0)
+    }
 }
 
 impl_confidential_commitment!(AssetCommitment, 0x0a, 0x0b);
 impl_confidential_commitment!(ValueCommitment, 0x08, 0x09);
-impl_confidential_commitment!(Nonce, 0x02, 0x03);
+impl_confidential_commitment!(NonceCommitment, 0x02, 0x03);
 
-impl From<PublicKey> for Nonce {
+impl From<PublicKey> for NonceCommitment {
     fn from(public_key: PublicKey) -> Self {
-        Nonce(public_key.serialize())
+        NonceCommitment(public_key.serialize())
     }
 }
 
+/// The nonce of an output, serialized as 33 bytes in both forms.
+///
+/// A null nonce is not represented by this type but by its absence, see
+/// [`ConfidentialTxOut::nonce`](crate::ConfidentialTxOut::nonce).
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub enum Nonce {
+    /// An explicit nonce, prefixed with `0x01`
+    Explicit([u8; 32]),
+    /// The sender's ephemeral public key, prefixed with `0x02` or `0x03`
+    Confidential(NonceCommitment),
+}
+
+impl Nonce {
+    /// The prefix of an explicit nonce.
+    pub const PREFIX_EXPLICIT: u8 = 0x01;
+
+    /// Parse a nonce from its 33-byte serialization, telling the two forms apart by the prefix.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, encode::Error> {
+        match bytes.split_first() {
+            Some((&Self::PREFIX_EXPLICIT, explicit)) if explicit.len() == 32 => {
+                let mut nonce = [0u8; 32];
+                nonce.copy_from_slice(explicit);
+
+                Ok(Nonce::Explicit(nonce))
+            }
+            Some((&Self::PREFIX_EXPLICIT, _)) => Err(encode::Error::ParseFailed(
+                "explicit nonce must be 33 bytes long",
+            )),
+            _ => Ok(Nonce::Confidential(NonceCommitment::from_slice(bytes)?)),
+        }
+    }
+
+    /// The confidential nonce, `None` if the nonce is explicit.
+    pub fn as_confidential(&self) -> Option<NonceCommitment> {
+        match self {
+            Nonce::Explicit(_) => None,
+            Nonce::Confidential(commitment) => Some(*commitment),
+        }
+    }
+
+    /// The prefix byte this nonce is serialized with.
+    pub fn prefix_byte(&self) -> u8 {
+        match self {
+            Nonce::Explicit(_) => Self::PREFIX_EXPLICIT,
+            Nonce::Confidential(commitment) => commitment.prefix_byte(),
+        }
+    }
+
+    pub fn encoded_length(&self) -> usize {
+        33
+    }
+}
+
+impl From<NonceCommitment> for Nonce {
+    fn from(commitment: NonceCommitment) -> Self {
+        Nonce::Confidential(commitment)
+    }
+}
+
+impl Encodable for Nonce {
+    fn consensus_encode<S: io::Write>(&self, mut s: S) -> Result<usize, encode::Error> {
+        match self {
+            Nonce::Explicit(nonce) => {
+                Ok(Self::PREFIX_EXPLICIT.consensus_encode(&mut s)?
+                    + nonce.consensus_encode(&mut s)?)
+            }
+            Nonce::Confidential(commitment) => commitment.consensus_encode(&mut s),
+        }
+    }
+}
+
+impl Decodable for Nonce {
+    fn consensus_decode<D: io::BufRead>(mut d: D) -> Result<Nonce, encode::Error> {
+        let bytes = <[u8; 33]>::consensus_decode(&mut d)?;
+
+        Nonce::from_slice(&bytes)
+    }
+}
+
+impl fmt::Display for Nonce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Nonce::Explicit(nonce) => {
+                write!(f, "{:02x}", Self::PREFIX_EXPLICIT)?;
+                for b in nonce.iter() {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+            Nonce::Confidential(commitment) => fmt::Display::fmt(commitment, f),
+        }
+    }
+}
+
+/// The nonce the sender of a confidential output derives to blind its rangeproof.
+///
+/// This is the SHA256 of the secp256k1 ECDH secret, which itself is the SHA256 of the
+/// compressed shared point, exactly like elementsd and libwally derive it. The receiver gets the
+/// same nonce from [`ecdh_nonce_recv`].
+pub fn ecdh_nonce(sender_ephemeral_sk: SecretKey, receiver_blinding_pk: PublicKey) -> [u8; 32] {
+    shared_nonce(&receiver_blinding_pk, &sender_ephemeral_sk)
+}
+
+/// The nonce the receiver of a confidential output derives from its blinding key and the
+/// output's [`NonceCommitment`] to unblind the output, see [`ecdh_nonce`].
+///
+/// Fails if the nonce is not a valid public key.
+pub fn ecdh_nonce_recv(
+    receiver_blinding_sk: SecretKey,
+    nonce: NonceCommitment,
+) -> Result<[u8; 32], secp256k1::Error> {
+    Ok(shared_nonce(&nonce.as_public_key()?, &receiver_blinding_sk))
+}
+
+fn shared_nonce(pk: &PublicKey, sk: &SecretKey) -> [u8; 32] {
+    let shared_secret = SharedSecret::new(pk, sk);
+
+    sha256::Hash::hash(&shared_secret[..]).into_inner()
+}
+
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct ValueBlindingFactor([u8; 32]);
 
@@ -261,6 +624,15 @@ impl From<[u8; 32]> for ValueBlindingFactor {
     }
 }
 
+/// Blinding factors are `Copy`, hence they cannot be zeroized on drop. Call
+/// [`Zeroize::zeroize`] explicitly once a blinding factor is no longer needed.
+#[cfg(feature = "zeroize")]
+impl Zeroize for ValueBlindingFactor {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct AssetBlindingFactor([u8; 32]);
 
@@ -288,9 +660,18 @@ impl From<[u8; 32]> for AssetBlindingFactor {
     }
 }
 
+/// See the note on the [`Zeroize`] implementation of [`ValueBlindingFactor`].
+#[cfg(feature = "zeroize")]
+impl Zeroize for AssetBlindingFactor {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn commitments() {
@@ -306,10 +687,344 @@ mod tests {
         commitment[0] = 42;
         assert!(AssetCommitment::from_slice(&commitment[..]).is_err());
 
-        let x = Nonce::from_commitment(0x02, &[1; 32]).unwrap();
+        let x = NonceCommitment::from_commitment(0x02, &[1; 32]).unwrap();
         let mut commitment = x.commitment();
-        assert_eq!(x, Nonce::from_slice(&commitment[..]).unwrap());
+        assert_eq!(x, NonceCommitment::from_slice(&commitment[..]).unwrap());
         commitment[0] = 42;
-        assert!(Nonce::from_slice(&commitment[..]).is_err());
+        assert!(NonceCommitment::from_slice(&commitment[..]).is_err());
+
+        assert!(ValueCommitment::from_slice(&[]).is_err());
+        assert!(AssetCommitment::from_slice(&[0x0a]).is_err());
+        assert!(NonceCommitment::from_slice(&[0x02; 34]).is_err());
+    }
+
+    #[test]
+    fn nonce_explicit_and_confidential() {
+        let mut bytes = [0x01; 33];
+        bytes[1..].copy_from_slice(&[7; 32]);
+        let explicit = Nonce::from_slice(&bytes).unwrap();
+        assert_eq!(explicit, Nonce::Explicit([7; 32]));
+        assert_eq!(explicit.as_confidential(), None);
+        assert_eq!(explicit.prefix_byte(), 0x01);
+        assert_eq!(encode::serialize(&explicit), bytes.to_vec());
+        assert_eq!(encode::deserialize::<Nonce>(&bytes).unwrap(), explicit);
+        assert_eq!(explicit.to_string(), hex::encode(&bytes[..]));
+
+        // the same bytes behind a public key prefix are a confidential nonce
+        bytes[0] = 0x02;
+        let commitment = NonceCommitment::from_slice(&bytes).unwrap();
+        let confidential = Nonce::from_slice(&bytes).unwrap();
+        assert_eq!(confidential, Nonce::Confidential(commitment));
+        assert_eq!(confidential.as_confidential(), Some(commitment));
+        assert_eq!(encode::serialize(&confidential), bytes.to_vec());
+        assert_eq!(confidential.to_string(), commitment.to_string());
+
+        assert!(Nonce::from_slice(&[0x01; 32]).is_err());
+        assert!(Nonce::from_slice(&[0x00; 33]).is_err());
+    }
+
+    #[test]
+    fn commitment_prefixes() {
+        assert_eq!(
+            (AssetCommitment::PREFIX_EVEN, AssetCommitment::PREFIX_ODD),
+            (0x0a, 0x0b)
+        );
+        assert_eq!(
+            (ValueCommitment::PREFIX_EVEN, ValueCommitment::PREFIX_ODD),
+            (0x08, 0x09)
+        );
+        assert_eq!(
+            (NonceCommitment::PREFIX_EVEN, NonceCommitment::PREFIX_ODD),
+            (0x02, 0x03)
+        );
+
+        assert!(ValueCommitment::is_valid_prefix(
+            ValueCommitment::PREFIX_EVEN
+        ));
+        assert!(!ValueCommitment::is_valid_prefix(
+            AssetCommitment::PREFIX_EVEN
+        ));
+        assert!(!NonceCommitment::is_valid_prefix(0x01));
+    }
+
+    #[test]
+    fn nonce_as_public_key() {
+        let secp = Secp256k1::new();
+        let public_key =
+            PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&[1; 32]).unwrap());
+
+        assert_eq!(
+            NonceCommitment::from(public_key).as_public_key(),
+            Ok(public_key)
+        );
+        // x = 5 is not on the curve
+        let mut xcoor = [0; 32];
+        xcoor[31] = 5;
+        assert!(NonceCommitment::from_commitment(0x02, &xcoor)
+            .unwrap()
+            .as_public_key()
+            .is_err());
+    }
+
+    #[test]
+    fn ecdh_nonce_vector() {
+        let secp = Secp256k1::new();
+        let sender_sk = SecretKey::from_slice(&[1; 32]).unwrap();
+        let receiver_sk = SecretKey::from_slice(&[2; 32]).unwrap();
+        let nonce = NonceCommitment::from(PublicKey::from_secret_key(&secp, &sender_sk));
+
+        let sender_nonce = ecdh_nonce(sender_sk, PublicKey::from_secret_key(&secp, &receiver_sk));
+
+        assert_eq!(
+            hex::encode(sender_nonce),
+            "38a133802db6d45d65dfb208818e1e18edfe9b4fc12438685be5db1277846c6a"
+        );
+        assert_eq!(ecdh_nonce_recv(receiver_sk, nonce).unwrap(), sender_nonce);
+    }
+
+    #[test]
+    fn ecdh_nonce_unblinds_wally_rangeproof() {
+        let secp = Secp256k1::new();
+        let sender_sk = SecretKey::from_slice(&[1; 32]).unwrap();
+        let receiver_sk = SecretKey::from_slice(&[2; 32]).unwrap();
+        let asset = AssetId::from_slice(&[3; 32]).unwrap();
+        let abf = AssetBlindingFactor::from([4; 32]);
+        let vbf = ValueBlindingFactor::from([5; 32]);
+        let generator = AssetCommitment::new(asset, abf);
+        let commitment = ValueCommitment::new(1_000, generator, vbf);
+        let script = crate::Script::new();
+
+        let rangeproof = crate::wally::asset_rangeproof(
+            1_000,
+            PublicKey::from_secret_key(&secp, &receiver_sk),
+            sender_sk,
+            asset,
+            abf,
+            vbf,
+            commitment,
+            &script,
+            generator,
+            1,
+            0,
+            52,
+        );
+        let nonce = NonceCommitment::from(PublicKey::from_secret_key(&secp, &sender_sk));
+
+        let (_, _, _, value) = crate::wally::asset_unblind_with_nonce(
+            ecdh_nonce_recv(receiver_sk, nonce).unwrap().to_vec(),
+            rangeproof,
+            encode::serialize(&commitment),
+            script,
+            encode::serialize(&generator),
+        );
+        assert_eq!(value, 1_000);
+    }
+
+    #[test]
+    fn asset_commitment_verify() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let abf = AssetBlindingFactor::from([2; 32]);
+        let commitment = AssetCommitment::new(asset, abf);
+
+        assert!(commitment.verify(asset, abf));
+        assert!(!commitment.verify(AssetId::from_slice(&[3; 32]).unwrap(), abf));
+        assert!(!commitment.verify(asset, AssetBlindingFactor::from([4; 32])));
+    }
+
+    #[test]
+    fn asset_commitment_from_point_hex() {
+        let commitment = AssetCommitment::from_commitment(0x0b, &[1; 32]).unwrap();
+
+        assert_eq!(
+            AssetCommitment::from_point_hex(&commitment.to_string()).unwrap(),
+            commitment
+        );
+        assert!(matches!(
+            AssetCommitment::from_point_hex(&format!("02{}", "01".repeat(32))),
+            Err(FromPointHexError::PublicKeyPrefix(0x02))
+        ));
+        assert!(matches!(
+            AssetCommitment::from_point_hex(&format!("08{}", "01".repeat(32))),
+            Err(FromPointHexError::Commitment(
+                encode::Error::InvalidConfidentialPrefix(0x08)
+            ))
+        ));
+        assert!(matches!(
+            AssetCommitment::from_point_hex(&format!("0b{}", "zz".repeat(32))),
+            Err(FromPointHexError::Hex(_))
+        ));
+        assert!(matches!(
+            AssetCommitment::from_point_hex(&commitment.to_string()[..64]),
+            Err(FromPointHexError::InvalidLength(64))
+        ));
+    }
+
+    macro_rules! commitment_proptests {
+        ($name:ident, $valid:ident, $invalid:ident, $prefixA:expr, $prefixB:expr) => {
+            proptest! {
+                #[test]
+                fn $valid(
+                    prefix in prop::sample::select(vec![$prefixA, $prefixB]),
+                    xcoor in prop::array::uniform32(any::<u8>()),
+                ) {
+                    let commitment = $name::from_commitment(prefix, &xcoor).unwrap();
+
+                    prop_assert_eq!(
+                        $name::from_slice(&commitment.commitment()).unwrap(),
+                        commitment
+                    );
+                    prop_assert_eq!(
+                        encode::deserialize::<$name>(&encode::serialize(&commitment)).unwrap(),
+                        commitment
+                    );
+                    prop_assert_eq!($name::from_hex(commitment.to_string()).unwrap(), commitment);
+                }
+
+                #[test]
+                fn $invalid(
+                    prefix in any::<u8>()
+                        .prop_filter("must be an invalid prefix", |p| !$name::is_valid_prefix(*p)),
+                    xcoor in prop::array::uniform32(any::<u8>()),
+                    short in prop::collection::vec(any::<u8>(), 0..33),
+                ) {
+                    let mut bytes = [0u8; 33];
+                    bytes[0] = prefix;
+                    bytes[1..].copy_from_slice(&xcoor);
+
+                    prop_assert!($name::from_slice(&bytes).is_err());
+                    prop_assert!(encode::deserialize::<$name>(&bytes).is_err());
+                    prop_assert!($name::from_slice(&short).is_err());
+                }
+            }
+        };
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn commitments_from_slice_ct() {
+        let mut bytes = [0x07; 33];
+
+        for &prefix in &[0x08, 0x09] {
+            bytes[0] = prefix;
+            let commitment = ValueCommitment::from_slice_ct(&bytes);
+            assert!(bool::from(commitment.is_some()));
+            assert_eq!(
+                commitment.unwrap(),
+                ValueCommitment::from_slice(&bytes).unwrap()
+            );
+        }
+
+        bytes[0] = 0x0a;
+        assert!(bool::from(ValueCommitment::from_slice_ct(&bytes).is_none()));
+        assert!(bool::from(AssetCommitment::from_slice_ct(&bytes).is_some()));
+        assert!(bool::from(
+            AssetCommitment::from_slice_ct(&bytes[..32]).is_none()
+        ));
+        assert!(bool::from(AssetCommitment::from_slice_ct(&[]).is_none()));
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn commitments_base64_roundtrip() {
+        let commitment = ValueCommitment::from_commitment(0x09, &[7; 32]).unwrap();
+        let encoded = commitment.to_base64();
+
+        assert_eq!(encoded, base64::encode(&commitment.commitment()[..]));
+        assert_eq!(ValueCommitment::from_base64(&encoded).unwrap(), commitment);
+        assert!(matches!(
+            ValueCommitment::from_base64("not base64!"),
+            Err(FromBase64Error::Base64(_))
+        ));
+        assert!(matches!(
+            AssetCommitment::from_base64(&encoded),
+            Err(FromBase64Error::Commitment(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn commitments_serde() {
+        let commitment = ValueCommitment::from_commitment(0x09, &[7; 32]).unwrap();
+
+        let json = serde_json::to_string(&commitment).unwrap();
+        assert_eq!(json, format!(r#""{}""#, commitment));
+        assert_eq!(
+            serde_json::from_str::<ValueCommitment>(&json).unwrap(),
+            commitment
+        );
+        assert!(serde_json::from_str::<AssetCommitment>(&json).is_err());
+
+        let bytes = bincode::serialize(&commitment).unwrap();
+        assert_eq!(bytes, commitment.commitment().to_vec());
+        assert_eq!(
+            bincode::deserialize::<ValueCommitment>(&bytes).unwrap(),
+            commitment
+        );
+        assert!(bincode::deserialize::<AssetCommitment>(&bytes).is_err());
+        assert!(bincode::deserialize::<ValueCommitment>(&bytes[..32]).is_err());
+    }
+
+    #[cfg(all(feature = "serde", feature = "base64"))]
+    #[test]
+    fn commitments_serde_base64() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(crate = "serde_crate")]
+        struct Output {
+            #[serde(with = "serde_base64")]
+            asset: AssetCommitment,
+            #[serde(with = "serde_base64")]
+            nonce: NonceCommitment,
+        }
+
+        let output = Output {
+            asset: AssetCommitment::from_commitment(0x0a, &[1; 32]).unwrap(),
+            nonce: NonceCommitment::from_commitment(0x02, &[2; 32]).unwrap(),
+        };
+        let json = serde_json::to_string(&output).unwrap();
+
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"asset":"{}","nonce":"{}"}}"#,
+                output.asset.to_base64(),
+                output.nonce.to_base64()
+            )
+        );
+        assert_eq!(serde_json::from_str::<Output>(&json).unwrap(), output);
     }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn blinding_factors_zeroize() {
+        let mut abf = AssetBlindingFactor::from([0xab; 32]);
+        let mut vbf = ValueBlindingFactor::from([0xcd; 32]);
+
+        abf.zeroize();
+        vbf.zeroize();
+
+        assert_eq!(abf.into_inner(), [0; 32]);
+        assert_eq!(vbf.into_inner(), [0; 32]);
+    }
+
+    commitment_proptests!(
+        AssetCommitment,
+        asset_commitment_roundtrips,
+        asset_commitment_rejects_invalid,
+        0x0au8,
+        0x0bu8
+    );
+    commitment_proptests!(
+        ValueCommitment,
+        value_commitment_roundtrips,
+        value_commitment_rejects_invalid,
+        0x08u8,
+        0x09u8
+    );
+    commitment_proptests!(
+        NonceCommitment,
+        nonce_roundtrips,
+        nonce_rejects_invalid,
+        0x02u8,
+        0x03u8
+    );
 }
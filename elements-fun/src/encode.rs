@@ -17,7 +17,13 @@
 
 use crate::transaction::{Transaction, TxIn, TxOut};
 pub use bitcoin::consensus::encode::MAX_VEC_SIZE;
-use bitcoin::{consensus::encode as btcenc, hashes::sha256};
+use bitcoin::{
+    consensus::encode as btcenc,
+    hashes::{
+        hex::{self, FromHex, ToHex},
+        sha256,
+    },
+};
 use std::{error, fmt, io, io::Cursor, mem};
 
 /// Encoding error
@@ -36,6 +42,13 @@ pub enum Error {
     ParseFailed(&'static str),
     /// Invalid prefix for the confidential type.
     InvalidConfidentialPrefix(u8),
+    /// The buffer is too small to hold the encoded data.
+    BufferTooSmall {
+        /// The number of bytes the encoding requires
+        required: usize,
+        /// The size of the buffer
+        available: usize,
+    },
 }
 
 impl fmt::Display for Error {
@@ -54,6 +67,14 @@ impl fmt::Display for Error {
             Error::InvalidConfidentialPrefix(p) => {
                 write!(f, "invalid confidential prefix: 0x{:02x}", p)
             }
+            Error::BufferTooSmall {
+                required,
+                available,
+            } => write!(
+                f,
+                "buffer too small: requires {} bytes, has {}",
+                required, available
+            ),
         }
     }
 }
@@ -102,6 +123,61 @@ pub fn serialize<T: Encodable + ?Sized>(data: &T) -> Vec<u8> {
     encoder.into_inner()
 }
 
+/// Encode an object into `buf`, returning the number of bytes written.
+///
+/// Unlike [`serialize`], this does not allocate. If `buf` is too small, it is filled with as much
+/// of the encoding as fits and [`Error::BufferTooSmall`] reports the size required.
+pub fn serialize_into<T: Encodable + ?Sized>(data: &T, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut writer = BufferWriter::new(buf);
+    data.consensus_encode(&mut writer)?;
+    writer.finish()
+}
+
+/// A writer into a fixed-size buffer which never fails or panics on overflow.
+///
+/// Bytes beyond the end of the buffer are dropped but still counted, so that
+/// [`finish`](BufferWriter::finish) can report how large the buffer would have to be.
+#[derive(Debug)]
+pub struct BufferWriter<'a>(&'a mut [u8], usize);
+
+impl<'a> BufferWriter<'a> {
+    /// Create a writer starting at the beginning of `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        BufferWriter(buf, 0)
+    }
+
+    /// The number of bytes written so far, including those which did not fit.
+    pub fn position(&self) -> usize {
+        self.1
+    }
+
+    /// The number of bytes written, or [`Error::BufferTooSmall`] if they did not all fit.
+    pub fn finish(self) -> Result<usize, Error> {
+        if self.1 > self.0.len() {
+            return Err(Error::BufferTooSmall {
+                required: self.1,
+                available: self.0.len(),
+            });
+        }
+        Ok(self.1)
+    }
+}
+
+impl<'a> io::Write for BufferWriter<'a> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let start = self.1.min(self.0.len());
+        let fits = data.len().min(self.0.len() - start);
+        self.0[start..start + fits].copy_from_slice(&data[..fits]);
+        self.1 += data.len();
+
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Encode an object into a hex-encoded string
 pub fn serialize_hex<T: Encodable + ?Sized>(data: &T) -> String {
     ::bitcoin::hashes::hex::ToHex::to_hex(&serialize(data)[..])
@@ -122,6 +198,47 @@ pub fn deserialize<T: Decodable>(data: &[u8]) -> Result<T, Error> {
     }
 }
 
+/// Like [`deserialize`], but reports the byte offset at which deserialization failed.
+pub fn deserialize_positioned<T: Decodable>(data: &[u8]) -> Result<T, PositionedError> {
+    let mut decoder = Cursor::new(data);
+    let result = T::consensus_decode(&mut decoder).and_then(|rv| {
+        if decoder.position() as usize == data.len() {
+            Ok(rv)
+        } else {
+            Err(Error::ParseFailed(
+                "data not consumed entirely when explicitly deserializing",
+            ))
+        }
+    });
+
+    result.map_err(|error| PositionedError {
+        position: decoder.position() as usize,
+        error,
+    })
+}
+
+/// A deserialization error together with the byte offset it occurred at.
+#[derive(Debug)]
+pub struct PositionedError {
+    /// The number of bytes successfully decoded before the error occurred. This is usually the
+    /// start of the field that failed to decode.
+    pub position: usize,
+    /// The actual error
+    pub error: Error,
+}
+
+impl fmt::Display for PositionedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte {}", self.error, self.position)
+    }
+}
+
+impl error::Error for PositionedError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
 /// Deserialize an object from a vector, but will not report an error if said deserialization
 /// doesn't consume the entire vector.
 pub fn deserialize_partial<T: Decodable>(data: &[u8]) -> Result<(T, usize), Error> {
@@ -132,6 +249,31 @@ pub fn deserialize_partial<T: Decodable>(data: &[u8]) -> Result<(T, usize), Erro
     Ok((rv, consumed))
 }
 
+/// Hex-encode 32 bytes in reverse order.
+///
+/// This is how txids, block hashes, asset ids and issuance entropy are displayed, while they are
+/// serialized and hashed in the order of `bytes`.
+pub fn reverse_hex(bytes: &[u8; 32]) -> String {
+    let mut reversed = *bytes;
+    reversed.reverse();
+
+    reversed.to_hex()
+}
+
+/// Parse hex as displayed by [`reverse_hex`] back into 32 bytes in serialization order.
+pub fn parse_reversed_hex(s: &str) -> Result<[u8; 32], hex::Error> {
+    let decoded = Vec::<u8>::from_hex(s)?;
+    if decoded.len() != 32 {
+        return Err(hex::Error::InvalidLength(64, s.len()));
+    }
+
+    let mut bytes = [0u8; 32];
+    for (byte, decoded) in bytes.iter_mut().zip(decoded.iter().rev()) {
+        *byte = *decoded;
+    }
+    Ok(bytes)
+}
+
 impl Encodable for sha256::Midstate {
     fn consensus_encode<W: io::Write>(&self, e: W) -> Result<usize, Error> {
         self.into_inner().consensus_encode(e)
@@ -163,14 +305,39 @@ macro_rules! impl_upstream {
 impl_upstream!(u8);
 impl_upstream!(u32);
 impl_upstream!(u64);
-impl_upstream!([u8; 32]);
 impl_upstream!(Box<[u8]>);
-impl_upstream!([u8; 33]);
 impl_upstream!(Vec<u8>);
 impl_upstream!(Vec<Vec<u8>>);
 impl_upstream!(btcenc::VarInt);
 impl_upstream!(::bitcoin::hashes::sha256d::Hash);
 
+// Fixed-size byte arrays
+//
+// TODO: Replace with a single `impl<const N: usize>` once the toolchain is bumped to >= 1.51.
+macro_rules! impl_array {
+    ($($size: expr),+) => {
+        $(
+            impl Encodable for [u8; $size] {
+                #[inline]
+                fn consensus_encode<W: io::Write>(&self, mut e: W) -> Result<usize, Error> {
+                    e.write_all(&self[..])?;
+                    Ok($size)
+                }
+            }
+
+            impl Decodable for [u8; $size] {
+                #[inline]
+                fn consensus_decode<D: io::BufRead>(mut d: D) -> Result<Self, Error> {
+                    let mut ret = [0u8; $size];
+                    d.read_exact(&mut ret)?;
+                    Ok(ret)
+                }
+            }
+        )+
+    };
+}
+impl_array!(2, 4, 8, 12, 16, 20, 32, 33, 64);
+
 // Vectors
 macro_rules! impl_vec {
     ($type: ty) => {
@@ -211,3 +378,162 @@ macro_rules! impl_vec {
 impl_vec!(TxIn);
 impl_vec!(TxOut);
 impl_vec!(Transaction);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash;
+
+    #[test]
+    fn byte_array_roundtrip() {
+        let bytes = [0xab; 64];
+        let encoded = serialize(&bytes);
+        assert_eq!(encoded, bytes.to_vec());
+        assert_eq!(deserialize::<[u8; 64]>(&encoded).unwrap()[..], bytes[..]);
+
+        let bytes = [0x01; 33];
+        assert_eq!(deserialize::<[u8; 33]>(&serialize(&bytes)).unwrap(), bytes);
+
+        assert!(deserialize::<[u8; 32]>(&[0x02; 31]).is_err());
+    }
+
+    #[test]
+    fn deserialize_reports_position() {
+        let tx: Transaction = hex_deserialize!(
+            "020000000001eb04b68e9a26d116046c76e8ff47332fb71dda90ff4bef5370f2\
+             5226d3bc09fc0000000000feffffff0201230f4f5d4b7c6fa845806ee4f67713\
+             459e1b69e8e60fcee2e4940c7a0d5de1b20100000002540bd71c001976a91448\
+             633e2c0ee9495dd3f9c43732c47f4702a362c888ac01230f4f5d4b7c6fa84580\
+             6ee4f67713459e1b69e8e60fcee2e4940c7a0d5de1b2010000000000000ce400\
+             0000000000"
+        );
+        let mut encoded = serialize(&tx);
+        assert_eq!(deserialize_positioned::<Transaction>(&encoded).unwrap(), tx);
+
+        // an invalid asset prefix at the start of the first output, detected once the whole
+        // commitment is read
+        encoded[6 + 41 + 1] = 0x07;
+        let error = deserialize_positioned::<Transaction>(&encoded).unwrap_err();
+        assert!(matches!(
+            error.error,
+            Error::InvalidConfidentialPrefix(0x07)
+        ));
+        assert_eq!(error.position, 6 + 41 + 1 + 33);
+
+        // truncated within the asset of the first output, which starts after its prefix
+        let mut encoded = serialize(&tx);
+        encoded.truncate(50);
+        let error = deserialize_positioned::<Transaction>(&encoded).unwrap_err();
+        assert_eq!(error.position, 6 + 41 + 1 + 1);
+        assert!(error.to_string().ends_with("at byte 49"));
+
+        let mut encoded = serialize(&tx);
+        let len = encoded.len();
+        encoded.push(0x00);
+        let error = deserialize_positioned::<Transaction>(&encoded).unwrap_err();
+        assert_eq!(error.position, len);
+    }
+
+    // Decoding is always strict about length prefixes as the upstream `VarInt` rejects
+    // non-minimal encodings, which would otherwise allow malleating the serialization.
+    #[test]
+    fn non_minimal_varint_rejected() {
+        let tx: Transaction = hex_deserialize!(
+            "020000000001eb04b68e9a26d116046c76e8ff47332fb71dda90ff4bef5370f2\
+             5226d3bc09fc0000000000feffffff0201230f4f5d4b7c6fa845806ee4f67713\
+             459e1b69e8e60fcee2e4940c7a0d5de1b20100000002540bd71c001976a91448\
+             633e2c0ee9495dd3f9c43732c47f4702a362c888ac01230f4f5d4b7c6fa84580\
+             6ee4f67713459e1b69e8e60fcee2e4940c7a0d5de1b2010000000000000ce400\
+             0000000000"
+        );
+        let encoded = serialize(&tx);
+
+        // the input count and the length of the first script_sig, encoded in three bytes
+        for &(position, value) in &[(5, 0x01), (6 + 36, 0x00)] {
+            assert_eq!(encoded[position], value);
+
+            let mut non_minimal = encoded.clone();
+            non_minimal.splice(position..position + 1, vec![0xfd, value, 0x00]);
+            assert!(matches!(
+                deserialize::<Transaction>(&non_minimal),
+                Err(Error::Bitcoin(btcenc::Error::NonMinimalVarInt))
+            ));
+        }
+    }
+
+    #[test]
+    fn reversed_hex() {
+        let txid: crate::Txid = "d606b563122409191e3b114a41d5611332dc58237ad5d2dccded302664fd56c4"
+            .parse()
+            .unwrap();
+        let internal = txid.into_inner();
+        assert_eq!(internal[0], 0xc4);
+        assert_eq!(internal[31], 0xd6);
+
+        assert_eq!(reverse_hex(&internal), txid.to_string());
+        assert_eq!(parse_reversed_hex(&txid.to_string()).unwrap(), internal);
+
+        assert!(matches!(
+            parse_reversed_hex("d606b5"),
+            Err(hex::Error::InvalidLength(64, 6))
+        ));
+        assert!(parse_reversed_hex(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn serialize_into_buffer() {
+        let data = vec![0xab_u8; 10];
+        let encoded = serialize(&data);
+
+        let mut buf = [0u8; 16];
+        assert_eq!(serialize_into(&data, &mut buf).unwrap(), 11);
+        assert_eq!(buf[..11], encoded[..]);
+
+        let mut buf = [0u8; 11];
+        assert_eq!(serialize_into(&data, &mut buf).unwrap(), 11);
+
+        let mut buf = [0u8; 4];
+        match serialize_into(&data, &mut buf) {
+            Err(Error::BufferTooSmall {
+                required: 11,
+                available: 4,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(buf[..], encoded[..4]);
+
+        let mut buf = [0u8; 4];
+        let mut writer = BufferWriter::new(&mut buf);
+        io::Write::write_all(&mut writer, &[1, 2, 3]).unwrap();
+        io::Write::write_all(&mut writer, &[4, 5]).unwrap();
+        assert_eq!(writer.position(), 5);
+        assert!(matches!(
+            writer.finish(),
+            Err(Error::BufferTooSmall {
+                required: 5,
+                available: 4
+            })
+        ));
+        assert_eq!(buf, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn length_prefixed_witness_fields() {
+        let witness = crate::TxOutWitness {
+            surjection_proof: vec![0x01; 3],
+            rangeproof: vec![0x02; 0xfd],
+        };
+        let encoded = serialize(&witness);
+        assert_eq!(encoded[..4], [0x03, 0x01, 0x01, 0x01]);
+        assert_eq!(encoded[4..7], [0xfd, 0xfd, 0x00]);
+        assert_eq!(
+            deserialize::<crate::TxOutWitness>(&encoded).unwrap(),
+            witness
+        );
+
+        // a length prefix beyond the allocation limit is rejected
+        let mut oversized = serialize(&btcenc::VarInt(MAX_VEC_SIZE as u64 + 1));
+        oversized.extend_from_slice(&[0x00; 16]);
+        assert!(deserialize::<crate::TxOutWitness>(&oversized).is_err());
+    }
+}
@@ -20,7 +20,7 @@ use crate::{
     transaction::OutPoint,
 };
 use bitcoin::hashes::{self, hex, sha256, sha256d, Hash};
-use std::{io, str::FromStr};
+use std::{fmt, io, str::FromStr};
 
 /// The zero hash.
 const ZERO32: [u8; 32] = [
@@ -63,7 +63,66 @@ impl ContractHash {
     }
 }
 
+/// The entropy of an issuance, from which the IDs of the issued asset and its reissuance token
+/// are derived.
+///
+/// Like [`AssetId`], the entropy is displayed in the reverse order of how it is serialized,
+/// which matches the `entropy` reported by the RPC interface of `elementsd`.
+#[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub struct AssetEntropy([u8; 32]);
+
+impl AssetEntropy {
+    /// Create an [AssetEntropy] from bytes in internal order.
+    pub fn from_inner(bytes: [u8; 32]) -> AssetEntropy {
+        AssetEntropy(bytes)
+    }
+
+    /// The bytes of the [AssetEntropy] in internal order.
+    pub fn into_inner(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl fmt::Display for AssetEntropy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&encode::reverse_hex(&self.0))
+    }
+}
+
+impl fmt::Debug for AssetEntropy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self, f)
+    }
+}
+
+impl FromStr for AssetEntropy {
+    type Err = hex::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        encode::parse_reversed_hex(s).map(AssetEntropy)
+    }
+}
+
+impl Encodable for AssetEntropy {
+    fn consensus_encode<W: io::Write>(&self, e: W) -> Result<usize, encode::Error> {
+        self.0.consensus_encode(e)
+    }
+}
+
+impl Decodable for AssetEntropy {
+    fn consensus_decode<D: io::BufRead>(d: D) -> Result<Self, encode::Error> {
+        Ok(AssetEntropy(Decodable::consensus_decode(d)?))
+    }
+}
+
 /// An issued asset ID.
+///
+/// Like txids, asset IDs are displayed in the reverse order of how they are serialized. To make
+/// it explicit which order a conversion expects, every method below names it:
+///
+/// - *internal* order is the order used in consensus serialization and by the hashing
+///   functions, i.e. the order of the bytes returned by [`AssetId::to_internal`].
+/// - *display* order is the order used by [`Display`](std::fmt::Display),
+///   [`FromStr`] and the RPC interface of `elementsd`.
 #[derive(Copy, Clone, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
 pub struct AssetId(sha256::Midstate);
 
@@ -78,16 +137,73 @@ impl AssetId {
         self.0
     }
 
-    /// Copies a byte slice into an AssetId object
+    /// Copies a byte slice in internal order into an AssetId object
+    ///
+    /// Any 32 bytes are accepted, which is what consensus does: decoding transactions, parsing
+    /// RPC responses and computing asset ids from entropy all go through this. Use
+    /// [`AssetId::from_slice_checked`] for asset ids that are about to end up in a new output.
     pub fn from_slice(sl: &[u8]) -> Result<AssetId, hashes::Error> {
         sha256::Midstate::from_slice(sl).map(AssetId)
     }
 
+    /// Like [`AssetId::from_slice`] but rejects the all-zero asset id.
+    ///
+    /// No asset can be issued with the all-zero id, so it is almost certainly the result of an
+    /// uninitialized value, e.g. [`AssetId::default`]. Use this wherever an asset id is taken
+    /// from user input, such as the asset of an output to be created.
+    pub fn from_slice_checked(sl: &[u8]) -> Result<AssetId, InvalidAssetId> {
+        let asset = AssetId::from_slice(sl).map_err(InvalidAssetId::Length)?;
+        if asset.is_null() {
+            return Err(InvalidAssetId::Zero);
+        }
+
+        Ok(asset)
+    }
+
+    /// Whether this is the all-zero asset id.
+    pub fn is_null(&self) -> bool {
+        self.to_internal() == ZERO32
+    }
+
+    /// Create an [AssetId] from bytes in internal order.
+    pub fn from_internal(bytes: [u8; 32]) -> AssetId {
+        AssetId(sha256::Midstate::from_inner(bytes))
+    }
+
+    /// Create an [AssetId] from bytes in display order.
+    pub fn from_display(mut bytes: [u8; 32]) -> AssetId {
+        bytes.reverse();
+        AssetId::from_internal(bytes)
+    }
+
+    /// The bytes of the [AssetId] in internal order.
+    pub fn to_internal(&self) -> [u8; 32] {
+        self.0.into_inner()
+    }
+
+    /// The bytes of the [AssetId] in display order.
+    pub fn to_display(&self) -> [u8; 32] {
+        let mut bytes = self.to_internal();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Parse an [AssetId] from hex in display order, as shown by `elementsd`.
+    ///
+    /// This is the same as [`FromStr`], spelled out to avoid confusion with the
+    /// [`hex::FromHex`](::hex::FromHex) implementation which expects internal order.
+    pub fn from_display_hex(s: &str) -> Result<AssetId, hex::Error> {
+        encode::parse_reversed_hex(s).map(AssetId::from_internal)
+    }
+
+    /// Hex-encode the [AssetId] in display order, same as its [`Display`](std::fmt::Display)
+    /// implementation.
+    pub fn to_display_hex(&self) -> String {
+        encode::reverse_hex(&self.to_internal())
+    }
+
     /// Generate the asset entropy from the issuance prevout and the contract hash.
-    pub fn generate_asset_entropy(
-        prevout: OutPoint,
-        contract_hash: ContractHash,
-    ) -> sha256::Midstate {
+    pub fn generate_asset_entropy(prevout: OutPoint, contract_hash: ContractHash) -> AssetEntropy {
         // E : entropy
         // I : prevout
         // C : contract
@@ -97,19 +213,21 @@ impl AssetId {
             prevout.consensus_encode(&mut enc).unwrap();
             sha256d::Hash::from_engine(enc)
         };
-        fast_merkle_root(&[prevout_hash.into_inner(), contract_hash.into_inner()])
+        AssetEntropy(
+            fast_merkle_root(&[prevout_hash.into_inner(), contract_hash.into_inner()]).into_inner(),
+        )
     }
 
     /// Calculate the asset ID from the asset entropy.
-    pub fn from_entropy(entropy: sha256::Midstate) -> AssetId {
+    pub fn from_entropy(entropy: AssetEntropy) -> AssetId {
         // H_a : asset tag
         // E   : entropy
         // H_a = H( E || 0 )
-        AssetId(fast_merkle_root(&[entropy.into_inner(), ZERO32]))
+        AssetId(fast_merkle_root(&[entropy.0, ZERO32]))
     }
 
     /// Calculate the reissuance token asset ID from the asset entropy.
-    pub fn reissuance_token_from_entropy(entropy: sha256::Midstate, confidential: bool) -> AssetId {
+    pub fn reissuance_token_from_entropy(entropy: AssetEntropy, confidential: bool) -> AssetId {
         // H_a : asset reissuance tag
         // E   : entropy
         // if not fConfidential:
@@ -120,10 +238,22 @@ impl AssetId {
             false => ONE32,
             true => TWO32,
         };
-        AssetId(fast_merkle_root(&[entropy.into_inner(), second]))
+        AssetId(fast_merkle_root(&[entropy.0, second]))
+    }
+
+    /// Calculate both the asset ID and the reissuance token asset ID from the asset entropy.
+    ///
+    /// `confidential` states whether the issuance amount is blinded, which changes the
+    /// reissuance token ID.
+    pub fn asset_ids_from_entropy(entropy: AssetEntropy, confidential: bool) -> (AssetId, AssetId) {
+        (
+            AssetId::from_entropy(entropy),
+            AssetId::reissuance_token_from_entropy(entropy, confidential),
+        )
     }
 }
 
+/// Parses hex in **internal** order, unlike [`FromStr`] and [`AssetId::from_display_hex`].
 impl ::hex::FromHex for AssetId {
     type Error = ::hex::FromHexError;
 
@@ -132,6 +262,7 @@ impl ::hex::FromHex for AssetId {
     }
 }
 
+/// Parses hex in display order.
 impl hex::FromHex for AssetId {
     fn from_byte_iter<I>(iter: I) -> Result<Self, hex::Error>
     where
@@ -190,6 +321,26 @@ impl ::serde::Serialize for AssetId {
     }
 }
 
+/// A byte slice is not a valid [AssetId] according to [`AssetId::from_slice_checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidAssetId {
+    /// The slice is not 32 bytes long.
+    Length(hashes::Error),
+    /// The asset id is all zeros.
+    Zero,
+}
+
+impl fmt::Display for InvalidAssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidAssetId::Length(e) => write!(f, "invalid asset id: {}", e),
+            InvalidAssetId::Zero => write!(f, "invalid asset id: all zeros"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidAssetId {}
+
 #[cfg(feature = "serde")]
 impl<'de> ::serde::Deserialize<'de> for AssetId {
     fn deserialize<D: ::serde::Deserializer<'de>>(d: D) -> Result<AssetId, D::Error> {
@@ -259,7 +410,25 @@ mod test {
     use super::*;
     use std::str::FromStr;
 
-    use bitcoin::hashes::{hex::FromHex, sha256};
+    use bitcoin::hashes::hex::FromHex;
+
+    #[test]
+    fn asset_id_from_slice_checked() {
+        assert_eq!(AssetId::from_slice(&ZERO32).unwrap(), AssetId::default());
+        assert!(AssetId::default().is_null());
+        assert_eq!(
+            AssetId::from_slice_checked(&ZERO32),
+            Err(InvalidAssetId::Zero)
+        );
+        assert!(matches!(
+            AssetId::from_slice_checked(&[1; 31]),
+            Err(InvalidAssetId::Length(_))
+        ));
+
+        let asset = AssetId::from_slice_checked(&ONE32).unwrap();
+        assert_eq!(asset, AssetId::from_slice(&ONE32).unwrap());
+        assert!(!asset.is_null());
+    }
 
     #[test]
     fn example_elements_core() {
@@ -271,7 +440,7 @@ mod test {
 
         let contract_hash = ContractHash::from_inner(ZERO32);
         let prevout = OutPoint::from_str(prevout_str).unwrap();
-        let entropy = sha256::Midstate::from_hex(entropy_hex).unwrap();
+        let entropy = AssetEntropy::from_str(entropy_hex).unwrap();
         assert_eq!(
             AssetId::generate_asset_entropy(prevout, contract_hash),
             entropy
@@ -283,6 +452,77 @@ mod test {
             AssetId::reissuance_token_from_entropy(entropy, false),
             token_id
         );
+        assert_eq!(
+            AssetId::asset_ids_from_entropy(entropy, false),
+            (asset_id, token_id)
+        );
+
+        // example test data from Elements Core 0.21 with prevout vout = 1 and a blinded issuance
+        let prevout_str = "c76664aa4be760056dcc39b59637eeea8f3c3c3b2aeefb9f23a7b99945a2931e:1";
+        let entropy_hex = "bc67a13736341d8ad19e558433483a38cae48a44a5a8b5598ca0b01b5f9f9f41";
+        let asset_id_hex = "2ec6c1a06e895b06fffb8dc36084255f890467fb906565b0c048d4c807b4a129";
+        let token_id_hex = "d09d205ff7c626ca98c91fed24787ff747fec62194ed1b7e6ef6cc775a1a1fdc";
+
+        let prevout = OutPoint::from_str(prevout_str).unwrap();
+        let entropy = AssetEntropy::from_str(entropy_hex).unwrap();
+        assert_eq!(
+            AssetId::generate_asset_entropy(prevout, contract_hash),
+            entropy
+        );
+        let asset_id = AssetId::from_hex(asset_id_hex).unwrap();
+        assert_eq!(AssetId::from_entropy(entropy), asset_id);
+        let token_id = AssetId::from_hex(token_id_hex).unwrap();
+        assert_eq!(
+            AssetId::reissuance_token_from_entropy(entropy, true),
+            token_id
+        );
+        assert_eq!(
+            AssetId::asset_ids_from_entropy(entropy, true),
+            (asset_id, token_id)
+        );
+    }
+
+    #[test]
+    fn asset_entropy_byte_orders() {
+        // the entropy of the issuance in `example_elements_core`
+        let display_hex = "746f447f691323502cad2ef646f932613d37a83aeaa2133185b316648df4b70a";
+        let internal_hex = "0ab7f48d6416b3853113a2ea3aa8373d6132f946f62ead2c502313697f446f74";
+        let mut internal = [0u8; 32];
+        internal.copy_from_slice(&Vec::<u8>::from_hex(internal_hex).unwrap());
+
+        let entropy = AssetEntropy::from_str(display_hex).unwrap();
+        assert_eq!(entropy, AssetEntropy::from_inner(internal));
+        assert_eq!(entropy.into_inner(), internal);
+        assert_eq!(entropy.to_string(), display_hex);
+        assert_eq!(encode::serialize(&entropy), internal.to_vec());
+        assert_eq!(
+            encode::deserialize::<AssetEntropy>(&internal).unwrap(),
+            entropy
+        );
+    }
+
+    #[test]
+    fn asset_id_byte_orders() {
+        let display_hex = "630ed6f9b176af03c0cd3f8aa430f9e7b4d988cf2d0b2f204322488f03b00bf8";
+        let internal_hex = "f80bb0038f482243202f0b2dcf88d9b4e7f930a48a3fcdc003af76b1f9d60e63";
+        let mut internal = [0u8; 32];
+        internal.copy_from_slice(&Vec::<u8>::from_hex(internal_hex).unwrap());
+        let mut display = internal;
+        display.reverse();
+
+        let asset_id = AssetId::from_display_hex(display_hex).unwrap();
+        assert_eq!(asset_id, AssetId::from_str(display_hex).unwrap());
+        assert_eq!(asset_id, AssetId::from_internal(internal));
+        assert_eq!(asset_id, AssetId::from_display(display));
+        assert_eq!(asset_id, AssetId::from_slice(&internal).unwrap());
+        assert_eq!(
+            asset_id,
+            <AssetId as ::hex::FromHex>::from_hex(internal_hex).unwrap()
+        );
+        assert_eq!(asset_id.to_internal(), internal);
+        assert_eq!(asset_id.to_display(), display);
+        assert_eq!(asset_id.to_display_hex(), display_hex);
+        assert_eq!(encode::serialize(&asset_id), internal.to_vec());
     }
 
     #[cfg(feature = "json-contract")]
@@ -0,0 +1,234 @@
+//! # Decoded transactions
+//!
+//! A structured view of a transaction for JSON APIs, modeled after the output of the
+//! `decoderawtransaction` RPC of elementsd.
+//!
+
+use crate::{
+    issuance::AssetId,
+    script::{Script, ScriptType},
+    transaction::{Transaction, TxIn, TxOut},
+    Txid, Wtxid,
+};
+use bitcoin::Amount;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A decoded transaction, see [`Transaction::decoded`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "serde_crate")]
+pub struct DecodedTransaction {
+    pub txid: Txid,
+    pub hash: Wtxid,
+    pub version: u32,
+    pub size: usize,
+    pub vsize: usize,
+    pub weight: usize,
+    pub locktime: u32,
+    pub vin: Vec<DecodedTxIn>,
+    pub vout: Vec<DecodedTxOut>,
+    /// Total fee per asset, in bitcoin
+    pub fee: HashMap<AssetId, f64>,
+}
+
+/// A decoded transaction input.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "serde_crate")]
+pub struct DecodedTxIn {
+    pub txid: Txid,
+    pub vout: u32,
+    #[serde(rename = "scriptSig")]
+    pub script_sig: DecodedScript,
+    pub is_pegin: bool,
+    pub sequence: u32,
+    /// Hex-encoded script witness, omitted if empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub txinwitness: Vec<String>,
+    /// Hex-encoded pegin witness, omitted if empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pegin_witness: Vec<String>,
+}
+
+/// A decoded transaction output.
+///
+/// Depending on whether the output is explicit or confidential, either `value` and `asset` or
+/// the respective commitments are present.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "serde_crate")]
+pub struct DecodedTxOut {
+    /// Value in bitcoin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    /// Hex-encoded value commitment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valuecommitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<AssetId>,
+    /// Hex-encoded asset commitment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assetcommitment: Option<String>,
+    /// Hex-encoded nonce commitment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitmentnonce: Option<String>,
+    pub n: usize,
+    #[serde(rename = "scriptPubKey")]
+    pub script_pubkey: DecodedScript,
+}
+
+/// A decoded script.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "serde_crate")]
+pub struct DecodedScript {
+    pub asm: String,
+    pub hex: String,
+    /// The type of a scriptPubKey, omitted for scriptSigs
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub script_type: Option<&'static str>,
+}
+
+impl Transaction {
+    /// Decode the transaction into a structure that serializes like the output of the
+    /// `decoderawtransaction` RPC of elementsd.
+    pub fn decoded(&self) -> DecodedTransaction {
+        let weight = self.get_weight();
+
+        DecodedTransaction {
+            txid: self.txid(),
+            hash: self.wtxid(),
+            version: self.version,
+            size: self.get_size(),
+            vsize: (weight + 3) / 4,
+            weight,
+            locktime: self.lock_time,
+            vin: self.input.iter().map(decode_txin).collect(),
+            vout: self
+                .output
+                .iter()
+                .enumerate()
+                .map(|(n, output)| decode_txout(n, output))
+                .collect(),
+            fee: self
+                .all_fees()
+                .into_iter()
+                .map(|(asset, fee)| (asset, Amount::from_sat(fee).as_btc()))
+                .collect(),
+        }
+    }
+}
+
+fn decode_txin(input: &TxIn) -> DecodedTxIn {
+    DecodedTxIn {
+        txid: input.previous_output.txid,
+        vout: input.previous_output.vout,
+        script_sig: decode_script(&input.script_sig, None),
+        is_pegin: input.is_pegin(),
+        sequence: input.sequence,
+        txinwitness: input
+            .witness
+            .script_witness
+            .iter()
+            .map(hex::encode)
+            .collect(),
+        pegin_witness: input
+            .witness
+            .pegin_witness
+            .iter()
+            .map(hex::encode)
+            .collect(),
+    }
+}
+
+fn decode_txout(n: usize, output: &TxOut) -> DecodedTxOut {
+    let mut decoded = DecodedTxOut {
+        value: None,
+        valuecommitment: None,
+        asset: None,
+        assetcommitment: None,
+        commitmentnonce: None,
+        n,
+        script_pubkey: decode_script(
+            output.script_pubkey(),
+            Some(script_type(output.script_pubkey())),
+        ),
+    };
+
+    match output {
+        TxOut::Explicit(explicit) => {
+            decoded.value = Some(Amount::from_sat(explicit.value.0).as_btc());
+            decoded.asset = Some(explicit.asset.0);
+        }
+        TxOut::Confidential(confidential) => {
+            decoded.valuecommitment = Some(confidential.value.to_string());
+            decoded.assetcommitment = Some(confidential.asset.to_string());
+            decoded.commitmentnonce = confidential.nonce.map(|nonce| nonce.to_string());
+        }
+        TxOut::Null(_) => {}
+    }
+
+    decoded
+}
+
+fn decode_script(script: &Script, script_type: Option<&'static str>) -> DecodedScript {
+    DecodedScript {
+        asm: script.asm(),
+        hex: hex::encode(script.as_bytes()),
+        script_type,
+    }
+}
+
+/// The type of a scriptPubKey as named by elementsd.
+fn script_type(script: &Script) -> &'static str {
+    match script.output_type() {
+        ScriptType::Fee => "fee",
+        ScriptType::P2pkh => "pubkeyhash",
+        ScriptType::P2sh => "scripthash",
+        ScriptType::P2wpkh => "witness_v0_keyhash",
+        ScriptType::P2wsh => "witness_v0_scripthash",
+        ScriptType::P2tr => "witness_v1_taproot",
+        ScriptType::WitnessUnknown => "witness_unknown",
+        ScriptType::OpReturn => "nulldata",
+        ScriptType::Multisig => "multisig",
+        ScriptType::P2pk => "pubkey",
+        ScriptType::Unknown => "nonstandard",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoded_explicit_transaction() {
+        let tx: Transaction = hex_deserialize!(
+            "020000000001eb04b68e9a26d116046c76e8ff47332fb71dda90ff4bef5370f2\
+             5226d3bc09fc0000000000feffffff0201230f4f5d4b7c6fa845806ee4f67713\
+             459e1b69e8e60fcee2e4940c7a0d5de1b20100000002540bd71c001976a91448\
+             633e2c0ee9495dd3f9c43732c47f4702a362c888ac01230f4f5d4b7c6fa84580\
+             6ee4f67713459e1b69e8e60fcee2e4940c7a0d5de1b2010000000000000ce400\
+             0000000000"
+        );
+
+        let decoded = tx.decoded();
+        assert_eq!(decoded.txid, tx.txid());
+        assert_eq!(decoded.size, tx.get_size());
+        assert_eq!(decoded.vsize, tx.get_size());
+        assert_eq!(decoded.vin.len(), 1);
+        assert_eq!(decoded.vin[0].sequence, 0xffff_fffe);
+        assert_eq!(decoded.vout[0].value, Some(99.999967));
+        assert_eq!(
+            decoded.vout[0].script_pubkey.script_type,
+            Some("pubkeyhash")
+        );
+        assert_eq!(decoded.vout[1].script_pubkey.script_type, Some("fee"));
+
+        let json = serde_json::to_value(&decoded).unwrap();
+        let fee_asset = "b2e15d0d7a0c94e4e2ce0fe6e8691b9e451377f6e46e8045a86f7c4b5d4f0f23";
+        assert_eq!(json["fee"][fee_asset], 0.000033);
+        assert_eq!(json["vout"][1]["n"], 1);
+        assert_eq!(json["vout"][1]["asset"], fee_asset);
+        assert_eq!(json["vout"][0]["scriptPubKey"]["type"], "pubkeyhash");
+        assert!(json["vout"][0].get("valuecommitment").is_none());
+        assert!(json["vin"][0]["scriptSig"].get("type").is_none());
+        assert!(json["vin"][0].get("txinwitness").is_none());
+    }
+}
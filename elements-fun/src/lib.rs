@@ -30,6 +30,8 @@ pub extern crate bitcoin_hashes;
 
 #[cfg(feature = "serde")]
 extern crate serde_crate as serde;
+#[cfg(feature = "zeroize")]
+extern crate zeroize_crate as zeroize;
 
 #[macro_use]
 mod internal_macros;
@@ -43,9 +45,12 @@ pub mod encode;
 mod fast_merkle_root;
 pub mod hash_types;
 pub mod issuance;
+#[cfg(feature = "serde")]
+pub mod json;
 pub mod opcodes;
 pub mod script;
 pub mod slip77;
+pub mod taproot;
 mod transaction;
 pub mod wally;
 
@@ -53,15 +58,17 @@ pub mod wally;
 pub use bitcoin::{bech32, hashes, secp256k1};
 // export everything at the top level so it can be used as `elements::Transaction` etc.
 pub use ::bitcoin::consensus::encode::VarInt;
-pub use address::{Address, AddressError, AddressParams};
+pub use address::{blinding_pubkey, Address, AddressError, AddressParams};
 pub use bitcoin;
 pub use block::{Block, BlockHeader, ExtData as BlockExtData};
 pub use fast_merkle_root::fast_merkle_root;
 pub use hash_types::*;
-pub use issuance::{AssetId, ContractHash};
+pub use issuance::{AssetEntropy, AssetId, ContractHash, InvalidAssetId};
 pub use script::Script;
 pub use transaction::{
-    AssetIssuance, ConfidentialAssetIssuance, ConfidentialTxOut, ExplicitAsset,
-    ExplicitAssetIssuance, ExplicitTxOut, ExplicitValue, OutPoint, PeginData, PegoutData,
-    SigHashType, Transaction, TxIn, TxInWitness, TxOut, TxOutWitness, UnblindedTxOut,
+    AssetIssuance, Balance, BalanceError, CachedTxid, ConfidentialAssetIssuance, ConfidentialTxOut,
+    EncodeOpts, ExplicitAsset, ExplicitAssetIssuance, ExplicitTxOut, ExplicitValue,
+    MissingIssuedAsset, OutPoint, ParseSigHashTypeError, PeginData, PeginWitness, PegoutData,
+    SigHashBase, SigHashType, SumUnblindedError, Transaction, TxIn, TxInWitness, TxOut,
+    TxOutWitness, UnblindedTxOut,
 };
@@ -131,6 +131,44 @@ impl fmt::Display for Error {
     }
 }
 
+/// The standard template a scriptPubKey follows, see [`Script::output_type`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScriptType {
+    /// Pay to public key hash
+    P2pkh,
+    /// Pay to script hash
+    P2sh,
+    /// Pay to witness public key hash
+    P2wpkh,
+    /// Pay to witness script hash
+    P2wsh,
+    /// Pay to taproot, i.e. a version 1 witness program of 32 bytes
+    P2tr,
+    /// Any other witness program
+    WitnessUnknown,
+    /// Provably unspendable data carrier starting with `OP_RETURN`
+    OpReturn,
+    /// Bare `m-of-n` multisig
+    Multisig,
+    /// Pay to public key
+    P2pk,
+    /// The empty script of an Elements fee output
+    Fee,
+    /// Any other script
+    Unknown,
+}
+
+/// The number pushed by `OP_1` to `OP_16`.
+fn push_num(instruction: &Instruction) -> Option<usize> {
+    match instruction {
+        Instruction::Op(op) => match op.classify() {
+            opcodes::Class::PushNum(n) if n > 0 => Some(n as usize),
+            _ => None,
+        },
+        Instruction::PushBytes(_) => None,
+    }
+}
+
 /// Helper to encode an integer in script format
 fn build_scriptint(n: i64) -> Vec<u8> {
     if n == 0 {
@@ -399,6 +437,85 @@ impl Script {
             && self.0[1] == opcodes::all::OP_PUSHBYTES_20.into_u8()
     }
 
+    /// Checks whether a script pubkey is a p2tr output, i.e. a version 1 witness program of 32
+    /// bytes
+    #[inline]
+    pub fn is_v1_p2tr(&self) -> bool {
+        self.0.len() == 34
+            && self.0[0] == opcodes::all::OP_PUSHNUM_1.into_u8()
+            && self.0[1] == opcodes::all::OP_PUSHBYTES_32.into_u8()
+    }
+
+    /// Checks whether a script pubkey is a bare `OP_m <pubkey1> ... <pubkeyn> OP_n
+    /// OP_CHECKMULTISIG` output with `1 <= m <= n <= 16`
+    pub fn is_multisig(&self) -> bool {
+        self.parse_multisig().is_some()
+    }
+
+    /// Parse a bare multisig script, see [`is_multisig`](Script::is_multisig), into `m` and the
+    /// serialized pubkeys. The pubkeys are only checked to be 33 or 65 bytes long.
+    pub(crate) fn parse_multisig(&self) -> Option<(usize, Vec<&[u8]>)> {
+        let instructions = self
+            .instructions_minimal()
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+
+        let (first, n, keys) = match instructions.as_slice() {
+            [first, keys @ .., n, last]
+                if *last == Instruction::Op(opcodes::all::OP_CHECKMULTISIG) =>
+            {
+                (first, n, keys)
+            }
+            _ => return None,
+        };
+
+        let required = push_num(first)?;
+        let total = push_num(n)?;
+        let pubkeys = keys
+            .iter()
+            .map(|instruction| match *instruction {
+                Instruction::PushBytes(key) if key.len() == 33 || key.len() == 65 => Some(key),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if required > total || total != pubkeys.len() {
+            return None;
+        }
+
+        Some((required, pubkeys))
+    }
+
+    /// The standard template this script pubkey follows.
+    ///
+    /// An empty script is classified as [`ScriptType::Fee`], as that is what Elements fee
+    /// outputs pay to.
+    pub fn output_type(&self) -> ScriptType {
+        if self.is_empty() {
+            ScriptType::Fee
+        } else if self.is_p2pkh() {
+            ScriptType::P2pkh
+        } else if self.is_p2sh() {
+            ScriptType::P2sh
+        } else if self.is_v0_p2wpkh() {
+            ScriptType::P2wpkh
+        } else if self.is_v0_p2wsh() {
+            ScriptType::P2wsh
+        } else if self.is_v1_p2tr() {
+            ScriptType::P2tr
+        } else if self.is_witness_program() {
+            ScriptType::WitnessUnknown
+        } else if self.is_op_return() {
+            ScriptType::OpReturn
+        } else if self.is_multisig() {
+            ScriptType::Multisig
+        } else if self.is_p2pk() {
+            ScriptType::P2pk
+        } else {
+            ScriptType::Unknown
+        }
+    }
+
     /// Check if this is an OP_RETURN output
     pub fn is_op_return(&self) -> bool {
         !self.0.is_empty() && (opcodes::All::from(self.0[0]) == opcodes::all::OP_RETURN)
@@ -433,6 +550,32 @@ impl Script {
         }
     }
 
+    /// The script code to sign when the `separator_index`-th `OP_CODESEPARATOR` of the script,
+    /// counting from zero, is the last one executed before the signature check.
+    ///
+    /// This is the part of the script after that separator and is to be passed as the
+    /// `script_code` of the segwit v0 sighash in place of the whole witness script. Which
+    /// separator is executed last depends on the branches taken, so it has to be given.
+    /// Returns `None` if the script has fewer separators or cannot be parsed up to the one
+    /// requested.
+    pub fn script_code_after_separator(&self, separator_index: usize) -> Option<Script> {
+        let mut instructions = self.instructions();
+        let mut separators = 0;
+
+        loop {
+            match instructions.next()? {
+                Ok(Instruction::Op(opcodes::all::OP_CODESEPARATOR)) => {
+                    if separators == separator_index {
+                        return Some(Script::from(instructions.data.to_vec()));
+                    }
+                    separators += 1;
+                }
+                Ok(_) => {}
+                Err(_) => return None,
+            }
+        }
+    }
+
     /// Write the assembly decoding of the script to the formatter.
     pub fn fmt_asm(&self, f: &mut dyn fmt::Write) -> fmt::Result {
         let mut index = 0;
@@ -523,6 +666,10 @@ impl Script {
     }
 
     /// Get the assembly decoding of the script.
+    ///
+    /// Opcodes are rendered by name and pushes as hex. Opcodes without an assigned meaning are
+    /// rendered as `OP_RETURN_<n>` and a push running past the end of the script is rendered as
+    /// `<push past end>`.
     pub fn asm(&self) -> String {
         let mut buf = String::new();
         self.fmt_asm(&mut buf).unwrap();
@@ -1112,6 +1259,70 @@ mod test {
         assert_eq!(hex_script!("").is_op_return(), false);
     }
 
+    #[test]
+    fn script_code_after_separator() {
+        // OP_IF OP_CODESEPARATOR <0xab> OP_ELSE OP_CODESEPARATOR OP_ENDIF OP_CHECKSIG
+        let script = hex_script!("63ab01ab67ab68ac");
+
+        assert_eq!(
+            script.script_code_after_separator(0),
+            Some(hex_script!("01ab67ab68ac"))
+        );
+        // the pushed 0xab is data, not a separator
+        assert_eq!(
+            script.script_code_after_separator(1),
+            Some(hex_script!("68ac"))
+        );
+        assert_eq!(script.script_code_after_separator(2), None);
+        assert_eq!(
+            hex_script!("ab").script_code_after_separator(0),
+            Some(Script::new())
+        );
+
+        // a truncated push before the separator
+        assert_eq!(hex_script!("4c").script_code_after_separator(0), None);
+    }
+
+    #[test]
+    fn output_type() {
+        let key = "032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af";
+
+        let cases = [
+            (
+                "76a914ee61d57ab51b9d212335b1dba62794ac20d2bcf988ac".to_owned(),
+                ScriptType::P2pkh,
+            ),
+            (
+                "a914afa92d77cd3541b443771649572db096cf49bf8c87".to_owned(),
+                ScriptType::P2sh,
+            ),
+            (
+                "0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1".to_owned(),
+                ScriptType::P2wpkh,
+            ),
+            (
+                "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262".to_owned(),
+                ScriptType::P2wsh,
+            ),
+            (
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c".to_owned(),
+                ScriptType::P2tr,
+            ),
+            ("6a0401020304".to_owned(), ScriptType::OpReturn),
+            (format!("5121{}21{}52ae", key, key), ScriptType::Multisig),
+            (String::new(), ScriptType::Fee),
+            ("52020102".to_owned(), ScriptType::WitnessUnknown),
+            (format!("21{}ac", key), ScriptType::P2pk),
+            // 2-of-1 multisig
+            (format!("5221{}51ae", key), ScriptType::Unknown),
+            ("51".to_owned(), ScriptType::Unknown),
+        ];
+
+        for (hex, expected) in cases.iter() {
+            assert_eq!(hex_script!(hex).output_type(), *expected, "{}", hex);
+        }
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn script_json_serialize() {
@@ -1142,6 +1353,9 @@ mod test {
         // Elements Alpha peg-out transaction with some signatures removed for brevity. Mainly to test PUSHDATA1
         assert_eq!(hex_script!("0047304402202457e78cc1b7f50d0543863c27de75d07982bde8359b9e3316adec0aec165f2f02200203fd331c4e4a4a02f48cf1c291e2c0d6b2f7078a784b5b3649fca41f8794d401004cf1552103244e602b46755f24327142a0517288cebd159eccb6ccf41ea6edf1f601e9af952103bbbacc302d19d29dbfa62d23f37944ae19853cf260c745c2bea739c95328fcb721039227e83246bd51140fe93538b2301c9048be82ef2fb3c7fc5d78426ed6f609ad210229bf310c379b90033e2ecb07f77ecf9b8d59acb623ab7be25a0caed539e2e6472103703e2ed676936f10b3ce9149fa2d4a32060fb86fa9a70a4efe3f21d7ab90611921031e9b7c6022400a6bb0424bbcde14cff6c016b91ee3803926f3440abf5c146d05210334667f975f55a8455d515a2ef1c94fdfa3315f12319a14515d2a13d82831f62f57ae").asm(),
                    "OP_0 OP_PUSHBYTES_71 304402202457e78cc1b7f50d0543863c27de75d07982bde8359b9e3316adec0aec165f2f02200203fd331c4e4a4a02f48cf1c291e2c0d6b2f7078a784b5b3649fca41f8794d401 OP_0 OP_PUSHDATA1 552103244e602b46755f24327142a0517288cebd159eccb6ccf41ea6edf1f601e9af952103bbbacc302d19d29dbfa62d23f37944ae19853cf260c745c2bea739c95328fcb721039227e83246bd51140fe93538b2301c9048be82ef2fb3c7fc5d78426ed6f609ad210229bf310c379b90033e2ecb07f77ecf9b8d59acb623ab7be25a0caed539e2e6472103703e2ed676936f10b3ce9149fa2d4a32060fb86fa9a70a4efe3f21d7ab90611921031e9b7c6022400a6bb0424bbcde14cff6c016b91ee3803926f3440abf5c146d05210334667f975f55a8455d515a2ef1c94fdfa3315f12319a14515d2a13d82831f62f57ae");
+        // unassigned opcodes and truncated pushes
+        assert_eq!(hex_script!("ff76").asm(), "OP_RETURN_255 OP_DUP");
+        assert_eq!(hex_script!("0301").asm(), "OP_PUSHBYTES_3 <push past end>");
     }
 
     #[test]
@@ -0,0 +1,174 @@
+// Rust Elements Library
+// Written in 2020 by
+//   The Elements developers
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Taproot
+//!
+//! Hashes of the Taproot script tree as defined by BIP341, with the domain separation Elements
+//! applies on top: all tags carry an `/elements` suffix and tapscript has its own leaf version.
+
+use crate::{encode::Encodable, Script, VarInt};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+
+/// The leaf version of tapscript in Elements.
+///
+/// Bitcoin uses `0xc0` instead, which keeps Elements tapscript leaves from being valid on
+/// Bitcoin and vice versa.
+pub const TAPROOT_LEAF_TAPSCRIPT: u8 = 0xc4;
+
+hash_newtype!(
+    TapLeafHash,
+    sha256::Hash,
+    32,
+    doc = "The tagged hash of a leaf of a Taproot script tree."
+);
+hash_newtype!(
+    TapBranchHash,
+    sha256::Hash,
+    32,
+    doc = "The tagged hash of an inner node of a Taproot script tree."
+);
+
+impl TapLeafHash {
+    /// Hash a leaf consisting of `script` with the given leaf version, usually
+    /// [`TAPROOT_LEAF_TAPSCRIPT`].
+    pub fn from_script(script: &Script, leaf_version: u8) -> TapLeafHash {
+        TapLeafHash::from_engine(leaf_engine("TapLeaf/elements", script, leaf_version))
+    }
+}
+
+impl TapBranchHash {
+    /// Hash the two children of an inner node, each either a [`TapLeafHash`] or a
+    /// [`TapBranchHash`].
+    ///
+    /// The children are sorted before hashing, so their order does not matter. This allows
+    /// proving membership of a leaf with just the hashes along its merkle path.
+    pub fn from_node_hashes(a: sha256::Hash, b: sha256::Hash) -> TapBranchHash {
+        TapBranchHash::from_engine(branch_engine("TapBranch/elements", a, b))
+    }
+}
+
+/// A SHA256 engine fed with the BIP341 leaf of `script`, tagged with `tag`.
+fn leaf_engine(tag: &str, script: &Script, leaf_version: u8) -> sha256::HashEngine {
+    let mut engine = tagged_engine(tag);
+    engine.input(&[leaf_version]);
+    VarInt(script.len() as u64)
+        .consensus_encode(&mut engine)
+        .expect("engines don't error");
+    engine.input(script.as_bytes());
+    engine
+}
+
+/// A SHA256 engine fed with the BIP341 inner node of `a` and `b`, tagged with `tag`.
+fn branch_engine(tag: &str, a: sha256::Hash, b: sha256::Hash) -> sha256::HashEngine {
+    let (first, second) = if a < b { (a, b) } else { (b, a) };
+
+    let mut engine = tagged_engine(tag);
+    engine.input(&first[..]);
+    engine.input(&second[..]);
+    engine
+}
+
+/// A SHA256 engine for the BIP340 tagged hash with `tag`, i.e. with `SHA256(tag)` input twice.
+fn tagged_engine(tag: &str) -> sha256::HashEngine {
+    let tag_hash = sha256::Hash::hash(tag.as_bytes());
+
+    let mut engine = sha256::Hash::engine();
+    engine.input(&tag_hash[..]);
+    engine.input(&tag_hash[..]);
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged_hash(tag: &str, msg: &[u8]) -> sha256::Hash {
+        let tag_hash = sha256::Hash::hash(tag.as_bytes());
+        let mut preimage = tag_hash[..].to_vec();
+        preimage.extend_from_slice(&tag_hash[..]);
+        preimage.extend_from_slice(msg);
+
+        sha256::Hash::hash(&preimage)
+    }
+
+    #[test]
+    fn leaf_hash() {
+        let script =
+            hex_script!("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac");
+        let leaf = TapLeafHash::from_script(&script, TAPROOT_LEAF_TAPSCRIPT);
+
+        let mut msg = vec![0xc4, 0x22];
+        msg.extend_from_slice(script.as_bytes());
+        assert_eq!(leaf.as_hash(), tagged_hash("TapLeaf/elements", &msg));
+
+        assert_ne!(TapLeafHash::from_script(&script, 0xc0), leaf);
+    }
+
+    #[test]
+    fn leaf_hash_of_long_script() {
+        let script = Script::from(vec![0x51; 300]);
+        let leaf = TapLeafHash::from_script(&script, TAPROOT_LEAF_TAPSCRIPT);
+
+        let mut msg = vec![0xc4, 0xfd, 0x2c, 0x01];
+        msg.extend_from_slice(script.as_bytes());
+        assert_eq!(leaf.as_hash(), tagged_hash("TapLeaf/elements", &msg));
+    }
+
+    #[test]
+    fn branch_hash_is_sorted() {
+        let a = TapLeafHash::from_script(&hex_script!("51"), TAPROOT_LEAF_TAPSCRIPT).as_hash();
+        let b = TapLeafHash::from_script(&hex_script!("52"), TAPROOT_LEAF_TAPSCRIPT).as_hash();
+        let (first, second) = if a < b { (a, b) } else { (b, a) };
+
+        let branch = TapBranchHash::from_node_hashes(a, b);
+        assert_eq!(branch, TapBranchHash::from_node_hashes(b, a));
+
+        let mut msg = first[..].to_vec();
+        msg.extend_from_slice(&second[..]);
+        assert_eq!(branch.as_hash(), tagged_hash("TapBranch/elements", &msg));
+
+        // branches combine further up the tree
+        let c = TapLeafHash::from_script(&hex_script!("53"), TAPROOT_LEAF_TAPSCRIPT).as_hash();
+        assert_ne!(
+            TapBranchHash::from_node_hashes(branch.as_hash(), c),
+            TapBranchHash::from_node_hashes(a, c)
+        );
+    }
+
+    // Elements only changes the tags and the tapscript leaf version, so the hashing itself is
+    // checked against scriptPubKey #3 of the BIP341 wallet test vectors.
+    #[test]
+    fn bip341_vectors() {
+        let script_a =
+            hex_script!("2044b178d64c32c4a05cc4f4d1407268f764c940d20ce97abfd44db5c3592b72fdac");
+        let script_b = hex_script!("07546170726f6f74");
+
+        let leaf_a = sha256::Hash::from_engine(leaf_engine("TapLeaf", &script_a, 0xc0));
+        let leaf_b = sha256::Hash::from_engine(leaf_engine("TapLeaf", &script_b, 0xc0));
+        assert_eq!(
+            hex::encode(&leaf_a[..]),
+            "64512fecdb5afa04f98839b50e6f0cb7b1e539bf6f205f67934083cdcc3c8d89"
+        );
+        assert_eq!(
+            hex::encode(&leaf_b[..]),
+            "2cb2b90daa543b544161530c925f285b06196940d6085ca9474d41dc3822c5cb"
+        );
+
+        let merkle_root = sha256::Hash::from_engine(branch_engine("TapBranch", leaf_a, leaf_b));
+        assert_eq!(
+            hex::encode(&merkle_root[..]),
+            "ab179431c28d3b68fb798957faf5497d69c883c6fb1e1cd9f81483d87bac90cc"
+        );
+    }
+}
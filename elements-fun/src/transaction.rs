@@ -17,25 +17,32 @@
 
 use crate::{
     confidential::{
-        AssetBlindingFactor, AssetCommitment, Nonce, ValueBlindingFactor, ValueCommitment,
+        AssetBlindingFactor, AssetCommitment, Nonce, NonceCommitment, ValueBlindingFactor,
+        ValueCommitment,
     },
     encode::{self, Decodable, Encodable, Error},
-    issuance::AssetId,
+    issuance::{AssetEntropy, AssetId, ContractHash},
     opcodes,
     script::Instruction,
+    slip77::MasterBlindingKey,
     wally::{asset_rangeproof, asset_surjectionproof, asset_unblind},
-    Address, Script, Txid, Wtxid,
+    Address, Script, Txid, WPubkeyHash, Wtxid,
 };
 use bitcoin::{
     self,
-    hashes::Hash,
+    hashes::{sha256d, Hash, HashEngine},
     secp256k1::{
         rand::{CryptoRng, RngCore},
-        PublicKey, Secp256k1, SecretKey, Signing,
+        Secp256k1, SecretKey, Signing,
     },
     VarInt,
 };
-use std::{collections::HashMap, fmt, io};
+use std::{
+    cell::Cell,
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt, io,
+    ops::Deref,
+};
 
 /// Elements transaction
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -66,8 +73,12 @@ pub struct TxIn {
     /// The reference to the previous output that is being used an an input
     pub previous_output: OutPoint,
     /// Flag indicating that `previous_outpoint` refers to something on the main chain
+    ///
+    /// Encoded as [`OutPoint::PEGIN_FLAG`] in the serialized `vout`.
     pub is_pegin: bool,
     /// Flag indicating that `previous_outpoint` has an asset issuance attached
+    ///
+    /// Encoded as [`OutPoint::ISSUANCE_FLAG`] in the serialized `vout`.
     pub has_issuance: bool,
     /// The script which pushes values on the stack which will cause
     /// the referenced output's script to accept
@@ -114,6 +125,14 @@ pub struct OutPoint {
 }
 
 impl OutPoint {
+    /// Bit of the serialized `vout` signalling that the input is a pegin.
+    ///
+    /// The flags are not part of [`OutPoint::vout`]; they are derived from
+    /// [`TxIn::is_pegin`] and [`TxIn::has_issuance`] when encoding and stripped when decoding.
+    pub const PEGIN_FLAG: u32 = 1 << 30;
+    /// Bit of the serialized `vout` signalling that the input has an asset issuance attached.
+    pub const ISSUANCE_FLAG: u32 = 1 << 31;
+
     /// Create a new outpoint.
     pub fn new(txid: Txid, vout: u32) -> OutPoint {
         OutPoint { txid, vout }
@@ -179,7 +198,7 @@ pub struct ConfidentialTxOut {
     pub asset: AssetCommitment,
     /// Committed amount
     pub value: ValueCommitment,
-    /// Nonce (ECDH key passed to recipient)
+    /// Nonce, usually the ECDH key passed to recipient
     ///
     /// TODO: I think this is only `None` if we spend an asset issuance.
     pub nonce: Option<Nonce>,
@@ -210,7 +229,7 @@ pub struct NullTxOut {
 pub struct ExplicitAssetIssuance {
     /// Zero for a new asset issuance; otherwise a blinding factor for the input
     pub asset_blinding_nonce: [u8; 32],
-    /// Freeform entropy field
+    /// Contract hash for a new asset issuance; otherwise the entropy of the reissued asset
     pub asset_entropy: [u8; 32],
     /// Amount of asset to issue
     pub amount: ExplicitValue,
@@ -227,7 +246,7 @@ pub struct ExplicitAssetIssuance {
 pub struct ConfidentialAssetIssuance {
     /// Zero for a new asset issuance; otherwise a blinding factor for the input
     pub asset_blinding_nonce: [u8; 32],
-    /// Freeform entropy field
+    /// Contract hash for a new asset issuance; otherwise the entropy of the reissued asset
     pub asset_entropy: [u8; 32],
     /// Amount of asset to issue
     pub amount: ValueCommitment,
@@ -244,7 +263,7 @@ pub struct ConfidentialAssetIssuance {
 pub struct NullAssetIssuance {
     /// Zero for a new asset issuance; otherwise a blinding factor for the input
     pub asset_blinding_nonce: [u8; 32],
-    /// Freeform entropy field
+    /// Contract hash for a new asset issuance; otherwise the entropy of the reissued asset
     pub asset_entropy: [u8; 32],
 }
 
@@ -256,6 +275,11 @@ pub struct NullAssetIssuance {
 )]
 pub struct ExplicitAsset(pub AssetId);
 
+/// An explicit amount, serialized as the prefix byte `0x01` followed by the amount as 8 bytes
+/// in big-endian order.
+///
+/// A value of zero is valid and encodes as `0x01` followed by eight zero bytes. It must not be
+/// confused with a null value, which is the single byte `0x00`.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
 #[cfg_attr(
     feature = "serde",
@@ -305,6 +329,77 @@ pub struct PeginData<'tx> {
     pub referenced_block: bitcoin::BlockHash,
 }
 
+/// The raw witness stack of a pegin input.
+///
+/// Unlike [`PeginData`], none of the elements are parsed, which guarantees that the witness
+/// re-encodes to exactly the bytes it was decoded from.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct PeginWitness {
+    /// The value, in satoshis, of the pegin; serialized as a little-endian `u64`
+    pub value: Vec<u8>,
+    /// Asset type being pegged in
+    pub asset: Vec<u8>,
+    /// Hash of genesis block of originating blockchain
+    pub genesis_hash: Vec<u8>,
+    /// The claim script that we should hash to tweak our address
+    pub claim_script: Vec<u8>,
+    /// Mainchain transaction
+    pub tx: Vec<u8>,
+    /// Merkle proof of transaction inclusion
+    pub merkle_proof: Vec<u8>,
+}
+
+impl PeginWitness {
+    fn from_stack(stack: &[Vec<u8>]) -> Option<Self> {
+        match stack {
+            [value, asset, genesis_hash, claim_script, tx, merkle_proof] => Some(PeginWitness {
+                value: value.clone(),
+                asset: asset.clone(),
+                genesis_hash: genesis_hash.clone(),
+                claim_script: claim_script.clone(),
+                tx: tx.clone(),
+                merkle_proof: merkle_proof.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Converts the pegin witness back into the witness stack of a `TxIn`.
+    pub fn into_stack(self) -> Vec<Vec<u8>> {
+        vec![
+            self.value,
+            self.asset,
+            self.genesis_hash,
+            self.claim_script,
+            self.tx,
+            self.merkle_proof,
+        ]
+    }
+}
+
+impl Encodable for PeginWitness {
+    fn consensus_encode<S: io::Write>(&self, mut s: S) -> Result<usize, encode::Error> {
+        let mut ret = VarInt(6).consensus_encode(&mut s)?;
+        ret += self.value.consensus_encode(&mut s)?;
+        ret += self.asset.consensus_encode(&mut s)?;
+        ret += self.genesis_hash.consensus_encode(&mut s)?;
+        ret += self.claim_script.consensus_encode(&mut s)?;
+        ret += self.tx.consensus_encode(&mut s)?;
+        ret += self.merkle_proof.consensus_encode(&mut s)?;
+        Ok(ret)
+    }
+}
+
+impl Decodable for PeginWitness {
+    fn consensus_decode<D: io::BufRead>(d: D) -> Result<Self, encode::Error> {
+        let stack = Vec::<Vec<u8>>::consensus_decode(d)?;
+
+        PeginWitness::from_stack(&stack).ok_or(encode::Error::ParseFailed(
+            "pegin witness must have 6 elements",
+        ))
+    }
+}
+
 /// Information about a pegout
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct PegoutData<'txo> {
@@ -326,58 +421,278 @@ impl Transaction {
         self.input.len() == 1 && self.input[0].is_coinbase()
     }
 
+    /// Whether any outpoint is spent by more than one input, which makes the transaction invalid.
+    pub fn has_duplicate_inputs(&self) -> bool {
+        let mut spent = HashSet::with_capacity(self.input.len());
+        !self
+            .input
+            .iter()
+            .all(|input| spent.insert(input.previous_output))
+    }
+
     /// Determines whether a transaction has any non-null witnesses
     pub fn has_witness(&self) -> bool {
         self.input.iter().any(|i| !i.witness.is_empty())
             || self.output.iter().any(|o| o.has_witness())
     }
 
+    /// Whether no part of the transaction is blinded, i.e. all outputs and all issuances are
+    /// explicit and no rangeproofs or surjection proofs are attached.
+    ///
+    /// The amounts of such a transaction can be checked with plain arithmetic. All other
+    /// transactions need their commitments and proofs to be verified, so this is what decides
+    /// whether a transaction has to be handed to a validator with the necessary cryptography.
+    pub fn is_fully_explicit(&self) -> bool {
+        let inputs_explicit = self.input.iter().all(|input| {
+            !matches!(input.asset_issuance, AssetIssuance::Confidential(_))
+                && input.witness.amount_rangeproof.is_empty()
+                && input.witness.inflation_keys_rangeproof.is_empty()
+        });
+        let outputs_explicit = self
+            .output
+            .iter()
+            .all(|output| !matches!(output, TxOut::Confidential(_)));
+
+        inputs_explicit && outputs_explicit
+    }
+
+    /// The number of inputs with an asset issuance or reissuance attached.
+    pub fn issuance_count(&self) -> usize {
+        self.input
+            .iter()
+            .filter(|input| input.has_issuance())
+            .count()
+    }
+
+    /// The number of inputs which are pegins.
+    pub fn pegin_count(&self) -> usize {
+        self.input.iter().filter(|input| input.is_pegin()).count()
+    }
+
+    /// The number of outputs with a blinded asset and value.
+    pub fn confidential_output_count(&self) -> usize {
+        self.output
+            .iter()
+            .filter(|output| matches!(output, TxOut::Confidential(_)))
+            .count()
+    }
+
+    /// Check that every asset issued by an input of this transaction ends up in an output.
+    ///
+    /// For each input with an issuance, the IDs of the issued asset and, for a new issuance, of
+    /// the reissuance token are derived from the issuance entropy. Each of them which is issued
+    /// a non-zero or blinded amount of has to be the asset of an output. Outputs with a blinded
+    /// asset could carry any asset, so the check only fails if there is none.
+    pub fn verify_issuances(&self) -> Result<(), MissingIssuedAsset> {
+        if self.confidential_output_count() > 0 {
+            return Ok(());
+        }
+
+        for (input_index, input) in self.input.iter().enumerate() {
+            for asset in input.issued_assets() {
+                let in_output = self.output.iter().any(|output| match output {
+                    TxOut::Explicit(explicit) => explicit.asset.0 == asset,
+                    _ => false,
+                });
+                if !in_output {
+                    return Err(MissingIssuedAsset { input_index, asset });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// All assets this transaction is known to involve, and whether it involves others which
+    /// are blinded.
+    ///
+    /// These are the assets of explicit outputs as well as the assets and reissuance tokens
+    /// issued by the inputs. The assets of confidential outputs cannot be listed without
+    /// unblinding them, the returned flag is set if there are any.
+    pub fn assets(&self) -> (BTreeSet<AssetId>, bool) {
+        let issued = self.input.iter().flat_map(TxIn::issued_assets);
+        let assets = self
+            .output
+            .iter()
+            .filter_map(TxOut::explicit_asset)
+            .chain(issued)
+            .collect();
+
+        (assets, self.confidential_output_count() > 0)
+    }
+
     /// Get the "weight" of this transaction; roughly equivalent to BIP141, in that witness data is
     /// counted as 1 while non-witness data is counted as 4.
+    ///
+    /// This is the full weight without the discount for confidential transactions. The
+    /// discounted weight Liquid calculates feerates with is a separate method,
+    /// [`discount_weight`](Transaction::discount_weight), rather than an option of this one.
     pub fn get_weight(&self) -> usize {
         self.get_scaled_size(4)
     }
 
+    /// Get the weight of this transaction with the discount Liquid applies to confidential
+    /// transactions when calculating their feerate.
+    ///
+    /// The rangeproofs and surjection proofs of the outputs are not counted, and value and nonce
+    /// commitments weigh only as much as an explicit value and an empty nonce would.
+    pub fn discount_weight(&self) -> usize {
+        let mut weight = self.get_weight();
+        let has_witness = self.has_witness();
+
+        for output in self.output.iter().filter_map(|o| o.as_confidential()) {
+            // without a witness there are no proofs to discount, otherwise the two bytes encoding
+            // empty proofs remain
+            if has_witness {
+                weight -= output.witness.encoded_length() - 2;
+            }
+            weight -= (33 - 9) * 4;
+            if let Some(Nonce::Confidential(_)) = output.nonce {
+                weight -= (33 - 1) * 4;
+            }
+        }
+        weight
+    }
+
+    /// Get the discounted virtual size of this transaction, see
+    /// [`discount_weight`](Transaction::discount_weight).
+    ///
+    /// Wallets targeting Liquid should use this to calculate fees as nodes do.
+    pub fn discount_vsize(&self) -> usize {
+        (self.discount_weight() + 3) / 4
+    }
+
+    /// The fee this transaction has to pay at least at a feerate of `feerate_sat_per_vb`, rounded
+    /// up to the next satoshi.
+    ///
+    /// With `discount` set, the size is the [`discount_vsize`](Transaction::discount_vsize),
+    /// which is what Liquid nodes use. Otherwise it is the full virtual size derived from
+    /// [`get_weight`](Transaction::get_weight), which is what Elements chains without the
+    /// discount use. Like in elementsd, the feerate is taken in satoshi per 1000 vbytes, i.e.
+    /// rounded to three decimal places.
+    ///
+    /// The fee output is part of the transaction, so it has to be present with a placeholder
+    /// value when calling this. Fees too large for a `u64`, including those of an infinite
+    /// feerate, saturate at `u64::MAX`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `feerate_sat_per_vb` is negative or NaN.
+    pub fn minimum_fee(&self, feerate_sat_per_vb: f64, discount: bool) -> u64 {
+        assert!(
+            feerate_sat_per_vb >= 0.0,
+            "feerate must not be negative or NaN, got {}",
+            feerate_sat_per_vb
+        );
+
+        let vsize = if discount {
+            self.discount_vsize()
+        } else {
+            (self.get_weight() + 3) / 4
+        };
+        let sat_per_kvb = (feerate_sat_per_vb * 1000.0).round() as u64;
+
+        match (vsize as u64).checked_mul(sat_per_kvb) {
+            Some(fee) => fee / 1000 + (fee % 1000 != 0) as u64,
+            None => u64::MAX,
+        }
+    }
+
+    /// Whether a fee of `provided_fee` in the policy asset pays at least `min_relay_feerate` in
+    /// satoshi per vbyte, as nodes require to relay the transaction.
+    ///
+    /// The size is the [`discount_vsize`](Transaction::discount_vsize), rounded like in
+    /// [`minimum_fee`](Transaction::minimum_fee). `provided_fee` is expected to be the value of
+    /// the fee output already in the transaction; as explicit values have a fixed size, the
+    /// value does not change the size it is compared against.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_relay_feerate` is negative or NaN.
+    pub fn meets_min_relay_fee(&self, provided_fee: u64, min_relay_feerate: f64) -> bool {
+        provided_fee >= self.minimum_fee(min_relay_feerate, true)
+    }
+
     /// Gets the regular byte-wise consensus-serialized size of this transaction.
     pub fn get_size(&self) -> usize {
         self.get_scaled_size(1)
     }
 
+    /// Returns a copy of the transaction without any rangeproofs and surjection proofs, neither
+    /// on the outputs nor on the issuances of the inputs. Script and pegin witnesses are kept.
+    ///
+    /// Comparing the [`get_weight`](Transaction::get_weight) of the result with the one of the
+    /// original transaction shows how much of the weight is due to the confidential proofs.
+    /// Note that this is not what fees on Liquid are paid for: the result still counts value and
+    /// nonce commitments at their full size, see
+    /// [`discount_weight`](Transaction::discount_weight) and
+    /// [`discount_vsize`](Transaction::discount_vsize) for the weight nodes use to calculate the
+    /// feerate.
+    pub fn without_witness_proofs(&self) -> Transaction {
+        let mut tx = self.clone();
+        for input in tx.input.iter_mut() {
+            input.witness.amount_rangeproof.clear();
+            input.witness.inflation_keys_rangeproof.clear();
+        }
+        for output in tx.output.iter_mut() {
+            if let TxOut::Confidential(confidential) = output {
+                confidential.witness = TxOutWitness::default();
+            }
+        }
+        tx
+    }
+
+    /// The total size in bytes of all rangeproofs and surjection proofs of the inputs and
+    /// outputs, i.e. of everything [`without_witness_proofs`] strips.
+    ///
+    /// [`without_witness_proofs`]: Transaction::without_witness_proofs
+    pub fn total_proof_bytes(&self) -> usize {
+        let input_proofs = self
+            .input
+            .iter()
+            .map(|input| {
+                input.witness.amount_rangeproof.len()
+                    + input.witness.inflation_keys_rangeproof.len()
+            })
+            .sum::<usize>();
+        let output_proofs = self
+            .output
+            .iter()
+            .filter_map(TxOut::as_confidential)
+            .map(|output| output.witness.surjection_proof.len() + output.witness.rangeproof.len())
+            .sum::<usize>();
+
+        input_proofs + output_proofs
+    }
+
     fn get_scaled_size(&self, scale_factor: usize) -> usize {
         let witness_flag = self.has_witness();
 
         let input_weight = self
             .input
             .iter()
-            .map(|input| {
-                scale_factor
-                    * (32 + 4 + 4 + // output + nSequence
-                    VarInt(input.script_sig.len() as u64).len() as usize +
-                    input.script_sig.len() + if input.has_issuance() {
-                    input.asset_issuance.encoded_length()
-                } else {
-                    0
-                }) + if witness_flag {
-                    input.witness.encoded_length()
-                } else {
-                    0
-                }
-            })
+            .map(|input| input.scaled_size(scale_factor, witness_flag))
             .sum::<usize>();
 
         let output_weight = self
             .output
             .iter()
-            .map(|output| {
-                scale_factor * output.encoded_length()
-                    + if witness_flag {
-                        output.witness_length()
-                    } else {
-                        0
-                    }
-            })
+            .map(|output| output.scaled_size(scale_factor, witness_flag))
             .sum::<usize>();
 
+        self.scaled_overhead_size(scale_factor) + input_weight + output_weight
+    }
+
+    /// The weight of the parts of this transaction which are not attributed to any input or
+    /// output: version, locktime, the input and output counts and the witness flag.
+    ///
+    /// Together with the [`TxIn::weight`] and [`TxOut::weight`] of all inputs and outputs, this
+    /// adds up to the [`get_weight`](Transaction::get_weight) of a transaction with witnesses.
+    pub fn overhead_weight(&self) -> usize {
+        self.scaled_overhead_size(4)
+    }
+
+    fn scaled_overhead_size(&self, scale_factor: usize) -> usize {
         scale_factor
             * (
                 4 + // version
@@ -387,18 +702,18 @@ impl Transaction {
                 1
                 // segwit flag byte (note this is *not* witness data in Elements)
             )
-            + input_weight
-            + output_weight
     }
 
     /// The txid of the transaction.
     pub fn txid(&self) -> Txid {
-        let mut enc = bitcoin::Txid::engine();
-        self.version.consensus_encode(&mut enc).unwrap();
-        0u8.consensus_encode(&mut enc).unwrap();
-        self.input.consensus_encode(&mut enc).unwrap();
-        self.output.consensus_encode(&mut enc).unwrap();
-        self.lock_time.consensus_encode(&mut enc).unwrap();
+        let mut enc = Txid::engine();
+        self.consensus_encode_with_opts(
+            &mut enc,
+            EncodeOpts {
+                include_witness: false,
+            },
+        )
+        .unwrap();
         Txid::from_engine(enc)
     }
 
@@ -409,6 +724,93 @@ impl Transaction {
         Wtxid::from_engine(enc)
     }
 
+    /// Returns the transaction with the input at `index` replaced by `input`.
+    ///
+    /// Like all of the following builder methods, this takes the transaction by value to make it
+    /// explicit that the result is a different transaction: signatures and any
+    /// [`SigHashCache`](crate::bip143::SigHashCache) of the original do not apply to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range of the inputs.
+    pub fn with_input(mut self, index: usize, input: TxIn) -> Self {
+        self.input[index] = input;
+        self
+    }
+
+    /// Returns the transaction with the output at `index` replaced by `output`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range of the outputs.
+    pub fn with_output(mut self, index: usize, output: TxOut) -> Self {
+        self.output[index] = output;
+        self
+    }
+
+    /// Returns the transaction with `input` appended to its inputs.
+    pub fn push_input(mut self, input: TxIn) -> Self {
+        self.input.push(input);
+        self
+    }
+
+    /// Returns the transaction with `output` appended to its outputs.
+    pub fn push_output(mut self, output: TxOut) -> Self {
+        self.output.push(output);
+        self
+    }
+
+    /// Iterate over the scriptPubKeys of the outputs together with their index.
+    pub fn output_scripts(&self) -> impl Iterator<Item = (usize, &Script)> {
+        self.output
+            .iter()
+            .map(|output| output.script_pubkey())
+            .enumerate()
+    }
+
+    /// The indices of all outputs paying to `script`.
+    pub fn find_outputs_to(&self, script: &Script) -> Vec<usize> {
+        self.output_scripts()
+            .filter(|(_, script_pubkey)| *script_pubkey == script)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The merkle root over the outputs of the transaction.
+    ///
+    /// This is for protocols committing to the output set as a tree and unrelated to the linear
+    /// output hash of BIP143. Each leaf is the `sha256d` hash of a serialized output and, like in
+    /// the merkle tree of a Bitcoin block, the last node of a level with an odd number of nodes
+    /// is paired with itself. Returns `None` for a transaction without outputs.
+    pub fn output_merkle_root(&self) -> Option<sha256d::Hash> {
+        let mut level = self
+            .output
+            .iter()
+            .map(|output| {
+                let mut enc = sha256d::Hash::engine();
+                output.consensus_encode(&mut enc).unwrap();
+                sha256d::Hash::from_engine(enc)
+            })
+            .collect::<Vec<_>>();
+        if level.is_empty() {
+            return None;
+        }
+
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| {
+                    let mut enc = sha256d::Hash::engine();
+                    enc.input(&pair[0][..]);
+                    enc.input(&pair[pair.len() - 1][..]);
+                    sha256d::Hash::from_engine(enc)
+                })
+                .collect();
+        }
+
+        Some(level[0])
+    }
+
     /// Get the total transaction fee in the given asset.
     pub fn fee_in(&self, asset: AssetId) -> u64 {
         // is_fee checks for explicit asset and value, so we can unwrap them here.
@@ -421,6 +823,26 @@ impl Transaction {
             .sum()
     }
 
+    /// The total value of `asset` in explicit outputs which are not fees.
+    ///
+    /// This includes any change, so wallets displaying the amount sent have to subtract their
+    /// own outputs. Returns `None` if there is a confidential output, as it could be of `asset`.
+    /// The sum saturates at `u64::MAX`, which no valid transaction gets close to.
+    pub fn total_sent(&self, asset: AssetId) -> Option<u64> {
+        if self.confidential_output_count() > 0 {
+            return None;
+        }
+
+        let total = self
+            .output
+            .iter()
+            .filter(|o| !o.is_fee())
+            .filter_map(|o| o.as_explicit())
+            .filter(|e| e.asset.0 == asset)
+            .fold(0u64, |total, o| total.saturating_add(o.value.0));
+        Some(total)
+    }
+
     /// Get all fees in all assets.
     pub fn all_fees(&self) -> HashMap<AssetId, u64> {
         let mut fees = HashMap::new();
@@ -437,6 +859,219 @@ impl Transaction {
         }
         fees
     }
+
+    /// The indices of the inputs which `pubkey` can sign, i.e. which spend a P2WPKH or
+    /// P2SH-P2WPKH output paying to its hash.
+    ///
+    /// `spent_outputs` are the outputs spent by the inputs, in the same order. Uncompressed keys
+    /// cannot sign for segwit outputs, so nothing is returned for them.
+    pub fn signable_inputs(
+        &self,
+        pubkey: &bitcoin::PublicKey,
+        spent_outputs: &[TxOut],
+    ) -> Vec<usize> {
+        if !pubkey.compressed {
+            return Vec::new();
+        }
+
+        let p2wpkh = Script::new_v0_wpkh(&WPubkeyHash::hash(&pubkey.to_bytes()));
+        let p2sh_p2wpkh = p2wpkh.to_p2sh();
+
+        spent_outputs
+            .iter()
+            .take(self.input.len())
+            .enumerate()
+            .filter(|(_, spent)| {
+                let script_pubkey = spent.script_pubkey();
+                *script_pubkey == p2wpkh || *script_pubkey == p2sh_p2wpkh
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Unblind every output of this transaction which pays to the wallet of
+    /// `master_blinding_key`, yielding `None` for all others.
+    ///
+    /// The blinding key of each output is derived from its scriptPubKey as per SLIP-77. Explicit
+    /// and null outputs as well as confidential outputs which cannot be unblinded with the
+    /// derived key yield `None`.
+    pub fn unblind_outputs(
+        &self,
+        master_blinding_key: &MasterBlindingKey,
+    ) -> Vec<Option<UnblindedTxOut>> {
+        self.output
+            .iter()
+            .map(|output| {
+                let confidential = output.as_confidential()?;
+                let blinding_key =
+                    master_blinding_key.derive_blinding_key(&confidential.script_pubkey);
+
+                confidential.unblind(blinding_key).ok()
+            })
+            .collect()
+    }
+
+    /// Sum up the values of all inputs per asset, given the unblinded outputs they spend.
+    ///
+    /// Fails if the output spent by one of the inputs is not part of `spent_outputs` or if the
+    /// sum of an asset exceeds `u64::MAX`.
+    pub fn sum_unblinded(
+        &self,
+        spent_outputs: &HashMap<OutPoint, UnblindedTxOut>,
+    ) -> Result<HashMap<AssetId, u64>, SumUnblindedError> {
+        let mut sums = HashMap::new();
+        for input in self.input.iter() {
+            let spent = spent_outputs
+                .get(&input.previous_output)
+                .ok_or(SumUnblindedError::MissingSpentOutput(input.previous_output))?;
+
+            let entry = sums.entry(spent.asset).or_insert(0u64);
+            *entry = entry
+                .checked_add(spent.value)
+                .ok_or(SumUnblindedError::Overflow(spent.asset))?;
+        }
+        Ok(sums)
+    }
+
+    /// Write a human-readable summary of the transaction to the formatter.
+    ///
+    /// Confidential commitments are abbreviated to their first few bytes and scripts are
+    /// rendered as assembly.
+    pub fn fmt_pretty(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(f, "txid: {}", self.txid())?;
+        writeln!(f, "version: {}", self.version)?;
+        writeln!(f, "lock_time: {}", self.lock_time)?;
+
+        writeln!(f, "inputs:")?;
+        for (index, input) in self.input.iter().enumerate() {
+            write!(
+                f,
+                "  #{} prevout: {}:{}",
+                index, input.previous_output.txid, input.previous_output.vout
+            )?;
+            if input.is_pegin() {
+                f.write_str(" (pegin)")?;
+            }
+            if input.has_issuance() {
+                f.write_str(" (issuance)")?;
+            }
+            writeln!(f)?;
+            writeln!(f, "     script_sig: {}", input.script_sig.asm())?;
+        }
+
+        writeln!(f, "outputs:")?;
+        for (index, output) in self.output.iter().enumerate() {
+            write!(f, "  #{} ", index)?;
+            match output {
+                TxOut::Explicit(explicit) => {
+                    write!(f, "asset: {} value: {}", explicit.asset.0, explicit.value.0)?;
+                }
+                TxOut::Confidential(confidential) => {
+                    f.write_str("asset: ")?;
+                    fmt_truncated_commitment(f, &confidential.asset.commitment())?;
+                    f.write_str(" value: ")?;
+                    fmt_truncated_commitment(f, &confidential.value.commitment())?;
+                }
+                TxOut::Null(_) => f.write_str("null")?,
+            }
+            if output.is_fee() {
+                f.write_str(" (fee)")?;
+            }
+            writeln!(f)?;
+            writeln!(f, "     script_pubkey: {}", output.script_pubkey().asm())?;
+        }
+
+        Ok(())
+    }
+
+    /// Get a human-readable summary of the transaction, see [`Transaction::fmt_pretty`].
+    pub fn pretty(&self) -> String {
+        let mut buf = String::new();
+        self.fmt_pretty(&mut buf).unwrap();
+        buf
+    }
+}
+
+/// Write a commitment as `confidential(<first bytes in hex>..)`.
+fn fmt_truncated_commitment(f: &mut dyn fmt::Write, commitment: &[u8; 33]) -> fmt::Result {
+    f.write_str("confidential(")?;
+    for b in commitment.iter().take(4) {
+        write!(f, "{:02x}", b)?;
+    }
+    f.write_str("..)")
+}
+
+/// Options for [`Transaction::consensus_encode_with_opts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeOpts {
+    /// Whether to serialize the witness of the inputs and outputs, if there is any
+    pub include_witness: bool,
+}
+
+/// Includes the witness, like [`Encodable`] does.
+impl Default for EncodeOpts {
+    fn default() -> Self {
+        EncodeOpts {
+            include_witness: true,
+        }
+    }
+}
+
+/// A transaction that memoizes its txid and wtxid after they have been computed once.
+///
+/// Like [`SigHashCache`](crate::bip143::SigHashCache), the cache is not invalidated
+/// automatically: the wrapper only hands out shared references to the transaction, but if `R`
+/// allows the transaction to be mutated behind its back (e.g. through a `RefCell`), the cached
+/// ids are stale afterwards. Create a new `CachedTxid` after every mutation.
+#[derive(Debug)]
+pub struct CachedTxid<R: Deref<Target = Transaction>> {
+    tx: R,
+    txid: Cell<Option<Txid>>,
+    wtxid: Cell<Option<Wtxid>>,
+}
+
+impl<R: Deref<Target = Transaction>> CachedTxid<R> {
+    pub fn new(tx: R) -> Self {
+        CachedTxid {
+            tx,
+            txid: Cell::new(None),
+            wtxid: Cell::new(None),
+        }
+    }
+
+    /// The txid of the transaction, only computed on the first call.
+    pub fn txid(&self) -> Txid {
+        match self.txid.get() {
+            Some(txid) => txid,
+            None => {
+                let txid = self.tx.txid();
+                self.txid.set(Some(txid));
+                txid
+            }
+        }
+    }
+
+    /// The wtxid of the transaction, only computed on the first call.
+    pub fn wtxid(&self) -> Wtxid {
+        match self.wtxid.get() {
+            Some(wtxid) => wtxid,
+            None => {
+                let wtxid = self.tx.wtxid();
+                self.wtxid.set(Some(wtxid));
+                wtxid
+            }
+        }
+    }
+
+    /// Access the wrapped transaction.
+    pub fn transaction(&self) -> &Transaction {
+        &self.tx
+    }
+
+    /// Returns the wrapped transaction, discarding the cache.
+    pub fn into_inner(self) -> R {
+        self.tx
+    }
 }
 
 impl TxIn {
@@ -486,23 +1121,238 @@ impl TxIn {
         })
     }
 
+    /// Returns the raw pegin witness of the input. Will return `None` if the input is not a
+    /// pegin or its witness does not consist of exactly 6 elements.
+    pub fn pegin_witness(&self) -> Option<PeginWitness> {
+        if !self.is_pegin {
+            return None;
+        }
+
+        PeginWitness::from_stack(&self.witness.pegin_witness)
+    }
+
     /// Helper to determine whether an input has an asset issuance attached
     pub fn has_issuance(&self) -> bool {
         self.has_issuance
     }
-}
-
-// TODO: Get rid of this by introducing a dedicated type for blinded addresses.
-#[derive(Debug)]
-pub struct NoBlindingKeyInAddress;
 
-impl fmt::Display for NoBlindingKeyInAddress {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "the address is missing a blinding key")
+    /// The amount of the asset issued by this input.
+    ///
+    /// Returns `None` if the input has no issuance attached or if the issuance is confidential,
+    /// as the amount of the latter is only known after unblinding it.
+    pub fn issued_amount(&self) -> Option<u64> {
+        match &self.asset_issuance {
+            AssetIssuance::Explicit(explicit) if self.has_issuance => Some(explicit.amount.0),
+            _ => None,
+        }
     }
-}
-
-impl std::error::Error for NoBlindingKeyInAddress {}
+
+    /// The amount of reissuance tokens issued by this input.
+    ///
+    /// Like [`TxIn::issued_amount`], this returns `None` if the input has no issuance attached
+    /// or if the issuance is confidential.
+    pub fn inflation_keys_amount(&self) -> Option<u64> {
+        match &self.asset_issuance {
+            AssetIssuance::Explicit(explicit) if self.has_issuance => {
+                Some(explicit.inflation_keys.0)
+            }
+            _ => None,
+        }
+    }
+
+    /// The weight this input adds to a transaction, with its witness counted as 1 and all other
+    /// data as 4.
+    ///
+    /// This assumes the transaction is serialized with witnesses, as is every transaction with
+    /// at least one witness. The witness of an input then takes up space even if it is empty.
+    pub fn weight(&self) -> usize {
+        self.scaled_size(4, true)
+    }
+
+    fn scaled_size(&self, scale_factor: usize, witness_flag: bool) -> usize {
+        // outpoint and nSequence
+        let mut base_size = 32 + 4 + 4;
+        base_size += VarInt(self.script_sig.len() as u64).len() as usize + self.script_sig.len();
+        if self.has_issuance() {
+            base_size += self.asset_issuance.encoded_length();
+        }
+
+        let witness_size = if witness_flag {
+            self.witness.encoded_length()
+        } else {
+            0
+        };
+
+        scale_factor * base_size + witness_size
+    }
+
+    /// The assets this input issues: the asset if a non-zero or blinded amount of it is issued,
+    /// and the reissuance token if this is a new issuance of non-zero or blinded inflation keys.
+    fn issued_assets(&self) -> Vec<AssetId> {
+        if !self.has_issuance() {
+            return Vec::new();
+        }
+
+        let issuance = &self.asset_issuance;
+        let (amount_issued, inflation_keys_issued, confidential) = match issuance {
+            AssetIssuance::Explicit(explicit) => {
+                (explicit.amount.0 > 0, explicit.inflation_keys.0 > 0, false)
+            }
+            AssetIssuance::Confidential(confidential) => {
+                (true, confidential.inflation_keys.is_some(), true)
+            }
+            AssetIssuance::Null(_) => return Vec::new(),
+        };
+
+        let entropy = if issuance.is_new_issuance() {
+            AssetId::generate_asset_entropy(
+                self.previous_output,
+                ContractHash::from_inner(issuance.asset_entropy()),
+            )
+        } else {
+            AssetEntropy::from_inner(issuance.asset_entropy())
+        };
+        let (asset, token) = AssetId::asset_ids_from_entropy(entropy, confidential);
+
+        let mut issued = Vec::with_capacity(2);
+        if amount_issued {
+            issued.push(asset);
+        }
+        if inflation_keys_issued && issuance.is_new_issuance() {
+            issued.push(token);
+        }
+        issued
+    }
+}
+
+// TODO: Get rid of this by introducing a dedicated type for blinded addresses.
+#[derive(Debug)]
+pub struct NoBlindingKeyInAddress;
+
+impl fmt::Display for NoBlindingKeyInAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the address is missing a blinding key")
+    }
+}
+
+impl std::error::Error for NoBlindingKeyInAddress {}
+
+/// Summing up the values of the inputs of a transaction failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SumUnblindedError {
+    /// The output spent by an input was not provided
+    MissingSpentOutput(OutPoint),
+    /// The sum of the asset exceeds `u64::MAX`
+    Overflow(AssetId),
+}
+
+impl fmt::Display for SumUnblindedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SumUnblindedError::MissingSpentOutput(outpoint) => {
+                write!(f, "missing spent output {}", outpoint)
+            }
+            SumUnblindedError::Overflow(asset) => write!(f, "sum of asset {} overflows", asset),
+        }
+    }
+}
+
+impl std::error::Error for SumUnblindedError {}
+
+/// An asset issued by an input is not the asset of any output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingIssuedAsset {
+    /// Index of the input with the issuance
+    pub input_index: usize,
+    /// The issued asset or reissuance token
+    pub asset: AssetId,
+}
+
+impl fmt::Display for MissingIssuedAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "asset {} issued by input {} is not in any output",
+            self.asset, self.input_index
+        )
+    }
+}
+
+impl std::error::Error for MissingIssuedAsset {}
+
+/// The value per asset that is left after adding inputs and subtracting outputs and fees.
+///
+/// Unlike plain `u64` arithmetic, this never wraps: spending more of an asset than is available
+/// is an error, as a transaction doing so is invalid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Balance(HashMap<AssetId, u64>);
+
+impl Balance {
+    pub fn new() -> Self {
+        Balance::default()
+    }
+
+    /// Add `value` of `asset`, e.g. of an input.
+    pub fn add(&mut self, asset: AssetId, value: u64) -> Result<(), BalanceError> {
+        let balance = self.0.entry(asset).or_insert(0);
+        *balance = balance
+            .checked_add(value)
+            .ok_or(BalanceError::Overflow(asset))?;
+        Ok(())
+    }
+
+    /// Subtract `value` of `asset`, e.g. of an output or a fee.
+    ///
+    /// The balance is left unchanged if there is not enough of `asset`.
+    pub fn sub(&mut self, asset: AssetId, value: u64) -> Result<(), BalanceError> {
+        let balance = self.get(asset);
+        if balance < value {
+            return Err(BalanceError::Underflow {
+                asset,
+                missing: value - balance,
+            });
+        }
+        self.0.insert(asset, balance - value);
+        Ok(())
+    }
+
+    /// The remaining value of `asset`.
+    pub fn get(&self, asset: AssetId) -> u64 {
+        self.0.get(&asset).copied().unwrap_or(0)
+    }
+
+    /// Whether nothing is left of any asset, i.e. the inputs exactly pay for the outputs.
+    pub fn is_balanced(&self) -> bool {
+        self.0.values().all(|&value| value == 0)
+    }
+}
+
+/// Adding to or subtracting from a [`Balance`] failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceError {
+    /// The value of the asset exceeds `u64::MAX`
+    Overflow(AssetId),
+    /// More of the asset was subtracted than is available
+    Underflow {
+        /// The asset which is short
+        asset: AssetId,
+        /// How much of the asset is missing
+        missing: u64,
+    },
+}
+
+impl fmt::Display for BalanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BalanceError::Overflow(asset) => write!(f, "balance of asset {} overflows", asset),
+            BalanceError::Underflow { asset, missing } => {
+                write!(f, "asset {} is short by {}", asset, missing)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BalanceError {}
 
 impl TxOut {
     /// Creates a new confidential output that is **not** the last one in the transaction.
@@ -525,12 +1375,77 @@ impl TxOut {
         C: Signing,
     {
         let out_abf = AssetBlindingFactor::new(rng);
-        let out_asset = AssetCommitment::new(asset, out_abf);
-
         let out_vbf = ValueBlindingFactor::random(rng);
+
+        let txout = TxOut::new_confidential_with_blinding_factors(
+            rng, secp, value, address, asset, out_abf, out_vbf, inputs,
+        )?;
+
+        Ok((txout, out_abf, out_vbf))
+    }
+
+    /// Creates a new confidential output paying the asset and value of a previously unblinded
+    /// output to `address`, blinded with the given factors.
+    ///
+    /// This re-blinds e.g. change that was unblinded before for a new transaction. Like
+    /// [`TxOut::new_not_last_confidential`], the output must **not** be the last one in the
+    /// transaction, as `vbf` does not balance the blinding factors of the transaction.
+    pub fn reblind<R, C>(
+        rng: &mut R,
+        secp: &Secp256k1<C>,
+        secrets: &UnblindedTxOut,
+        address: Address,
+        abf: AssetBlindingFactor,
+        vbf: ValueBlindingFactor,
+        inputs: &[(
+            AssetId,
+            u64,
+            AssetCommitment,
+            AssetBlindingFactor,
+            ValueBlindingFactor,
+        )],
+    ) -> Result<Self, NoBlindingKeyInAddress>
+    where
+        R: RngCore + CryptoRng,
+        C: Signing,
+    {
+        TxOut::new_confidential_with_blinding_factors(
+            rng,
+            secp,
+            secrets.value,
+            address,
+            secrets.asset,
+            abf,
+            vbf,
+            inputs,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_confidential_with_blinding_factors<R, C>(
+        rng: &mut R,
+        secp: &Secp256k1<C>,
+        value: u64,
+        address: Address,
+        asset: AssetId,
+        out_abf: AssetBlindingFactor,
+        out_vbf: ValueBlindingFactor,
+        inputs: &[(
+            AssetId,
+            u64,
+            AssetCommitment,
+            AssetBlindingFactor,
+            ValueBlindingFactor,
+        )],
+    ) -> Result<Self, NoBlindingKeyInAddress>
+    where
+        R: RngCore + CryptoRng,
+        C: Signing,
+    {
+        let out_asset = AssetCommitment::new(asset, out_abf);
         let value_commitment = ValueCommitment::new(value, out_asset, out_vbf);
 
-        let (nonce, sender_ephemeral_sk) = Nonce::new(rng, secp);
+        let (nonce, sender_ephemeral_sk) = NonceCommitment::new(rng, secp);
 
         let range_proof = asset_rangeproof(
             value,
@@ -557,18 +1472,16 @@ impl TxOut {
 
         let surjection_proof = asset_surjectionproof(rng, asset, out_abf, out_asset, &inputs);
 
-        let txout = TxOut::Confidential(ConfidentialTxOut {
-            asset: out_asset,
-            value: value_commitment,
-            nonce: Some(nonce),
-            script_pubkey: address.script_pubkey(),
-            witness: TxOutWitness {
-                surjection_proof,
-                rangeproof: range_proof,
-            },
-        });
+        let txout = TxOut::new_confidential_with_proofs(
+            out_asset,
+            value_commitment,
+            Some(Nonce::Confidential(nonce)),
+            address.script_pubkey(),
+            range_proof,
+            surjection_proof,
+        );
 
-        Ok((txout, out_abf, out_vbf))
+        Ok(txout)
     }
 
     /// Creates a new confidential output that IS the last one in the transaction.
@@ -603,7 +1516,7 @@ impl TxOut {
         let out_vbf = ValueBlindingFactor::last(value, out_abf, &value_blind_inputs, &outputs);
         let value_commitment = ValueCommitment::new(value, out_asset, out_vbf);
 
-        let (nonce, sender_ephemeral_sk) = Nonce::new(rng, secp);
+        let (nonce, sender_ephemeral_sk) = NonceCommitment::new(rng, secp);
 
         let range_proof = asset_rangeproof(
             value,
@@ -625,18 +1538,40 @@ impl TxOut {
         let surjection_proof =
             asset_surjectionproof(rng, asset, out_abf, out_asset, &surjection_proof_inputs);
 
-        let txout = TxOut::Confidential(ConfidentialTxOut {
-            asset: out_asset,
-            value: value_commitment,
-            nonce: Some(nonce),
-            script_pubkey: address.script_pubkey(),
+        let txout = TxOut::new_confidential_with_proofs(
+            out_asset,
+            value_commitment,
+            Some(Nonce::Confidential(nonce)),
+            address.script_pubkey(),
+            range_proof,
+            surjection_proof,
+        );
+
+        Ok(txout)
+    }
+
+    /// Assembles a confidential output from commitments and proofs which have already been
+    /// computed, e.g. by another wallet or for a deterministic test fixture.
+    ///
+    /// Neither the proofs nor whether they match the commitments are checked.
+    pub fn new_confidential_with_proofs(
+        asset: AssetCommitment,
+        value: ValueCommitment,
+        nonce: Option<Nonce>,
+        script_pubkey: Script,
+        rangeproof: Vec<u8>,
+        surjection_proof: Vec<u8>,
+    ) -> Self {
+        TxOut::Confidential(ConfidentialTxOut {
+            asset,
+            value,
+            nonce,
+            script_pubkey,
             witness: TxOutWitness {
                 surjection_proof,
-                rangeproof: range_proof,
+                rangeproof,
             },
-        });
-
-        Ok(txout)
+        })
     }
 
     pub fn new_explicit(asset: AssetId, value: u64, script_pubkey: Script) -> Self {
@@ -680,6 +1615,26 @@ impl TxOut {
         }
     }
 
+    /// The prefix bytes of the asset, value and nonce of this output, in that order.
+    ///
+    /// These are `0x00` for a null field, `0x01` for an explicit field and the parity-preserving
+    /// prefix of the commitment otherwise.
+    pub fn prefix_bytes(&self) -> (u8, u8, u8) {
+        match self {
+            Self::Explicit(explicit) => (
+                explicit.asset.prefix_byte(),
+                explicit.value.prefix_byte(),
+                0,
+            ),
+            Self::Confidential(confidential) => (
+                confidential.asset.prefix_byte(),
+                confidential.value.prefix_byte(),
+                confidential.nonce.map_or(0, |nonce| nonce.prefix_byte()),
+            ),
+            Self::Null(_) => (0, 0, 0),
+        }
+    }
+
     pub fn encoded_length(&self) -> usize {
         match self {
             Self::Confidential(inner) => inner.encoded_length(),
@@ -695,6 +1650,24 @@ impl TxOut {
         }
     }
 
+    /// The weight this output adds to a transaction, with its rangeproof and surjection proof
+    /// counted as 1 and all other data as 4.
+    ///
+    /// Like [`TxIn::weight`], this assumes the transaction is serialized with witnesses.
+    pub fn weight(&self) -> usize {
+        self.scaled_size(4, true)
+    }
+
+    fn scaled_size(&self, scale_factor: usize, witness_flag: bool) -> usize {
+        let witness_size = if witness_flag {
+            self.witness_length()
+        } else {
+            0
+        };
+
+        scale_factor * self.encoded_length() + witness_size
+    }
+
     /// Whether this data represents nulldata (OP_RETURN followed by pushes,
     /// not necessarily minimal)
     pub fn is_null_data(&self) -> bool {
@@ -792,11 +1765,78 @@ impl TxOut {
         }
     }
 
-    /// Whether or not this output is a fee output
+    /// Whether or not this output is a fee output, i.e. an explicit output with an empty
+    /// scriptPubKey. The asset of the output is irrelevant.
+    ///
+    /// This matches `CTxOut::IsFee` of elementsd: an output with a provably unspendable, but
+    /// non-empty scriptPubKey such as `OP_RETURN` is a burn, not a fee.
     pub fn is_fee(&self) -> bool {
         self.script_pubkey().is_empty() && matches!(self, Self::Explicit(_))
     }
 
+    /// Whether the asset, value and nonce of this output are all null, as in some coinbase
+    /// outputs.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null(_))
+    }
+
+    /// The asset of this output if it is explicit, `None` if it is blinded or null.
+    pub fn explicit_asset(&self) -> Option<AssetId> {
+        self.as_explicit().map(|explicit| explicit.asset.0)
+    }
+
+    /// The value of this output if it is explicit, `None` if it is blinded or null.
+    pub fn explicit_value(&self) -> Option<u64> {
+        self.as_explicit().map(|explicit| explicit.value.0)
+    }
+
+    /// The nonce of this output, `None` if it is null.
+    ///
+    /// Explicit outputs always have a null nonce.
+    pub fn nonce(&self) -> Option<Nonce> {
+        self.as_confidential()
+            .and_then(|confidential| confidential.nonce)
+    }
+
+    /// Whether this output is dust at the given dust relay feerate in satoshi per 1000 bytes.
+    ///
+    /// Like in elementsd, the threshold is the fee for the serialized output plus the input
+    /// spending it. Explicit asset and value take 42 bytes instead of Bitcoin's 8 bytes of value,
+    /// which raises the threshold accordingly. Outputs with a committed value are never dust as
+    /// their value is unknown, neither are provably unspendable outputs. An explicit output with
+    /// a value of zero is therefore dust unless it is an `OP_RETURN` data carrier or the feerate
+    /// is zero.
+    pub fn is_dust(&self, dust_relay_fee: u64) -> bool {
+        let value = match self {
+            Self::Explicit(explicit) => explicit.value.0,
+            Self::Confidential(_) | Self::Null(_) => return false,
+        };
+        let script_pubkey = self.script_pubkey();
+        if script_pubkey.is_provably_unspendable() {
+            return false;
+        }
+
+        // outpoint, script_sig length, script_sig (witness discounted) and sequence
+        let spending_input_size = if script_pubkey.is_witness_program() {
+            32 + 4 + 1 + (107 / 4) + 4
+        } else {
+            32 + 4 + 1 + 107 + 4
+        };
+        let size = (self.encoded_length() + spending_input_size) as u64;
+
+        let fee = match dust_relay_fee.checked_mul(size) {
+            Some(fee) => fee,
+            // the threshold is above any value an output can have
+            None => return true,
+        };
+        let threshold = match fee / 1000 {
+            0 if dust_relay_fee > 0 => 1,
+            threshold => threshold,
+        };
+
+        value < threshold
+    }
+
     /// Extracts the minimum value from the rangeproof, if there is one, or returns 0.
     pub fn minimum_value(&self) -> u64 {
         let min_value = if self.script_pubkey().is_op_return() {
@@ -835,6 +1875,39 @@ impl TxOut {
 }
 
 impl AssetIssuance {
+    /// Zero for a new asset issuance; otherwise the blinding factor of the reissuance token
+    /// being spent.
+    pub fn asset_blinding_nonce(&self) -> [u8; 32] {
+        match self {
+            AssetIssuance::Null(inner) => inner.asset_blinding_nonce,
+            AssetIssuance::Explicit(inner) => inner.asset_blinding_nonce,
+            AssetIssuance::Confidential(inner) => inner.asset_blinding_nonce,
+        }
+    }
+
+    /// Contract hash for a new asset issuance; otherwise the entropy of the reissued asset.
+    pub fn asset_entropy(&self) -> [u8; 32] {
+        match self {
+            AssetIssuance::Null(inner) => inner.asset_entropy,
+            AssetIssuance::Explicit(inner) => inner.asset_entropy,
+            AssetIssuance::Confidential(inner) => inner.asset_entropy,
+        }
+    }
+
+    /// Whether this issues a new asset, i.e. the blinding nonce is all-zero.
+    ///
+    /// A [`AssetIssuance::Null`] issuance is neither a new issuance nor a reissuance.
+    pub fn is_new_issuance(&self) -> bool {
+        !matches!(self, AssetIssuance::Null(_)) && self.asset_blinding_nonce() == [0; 32]
+    }
+
+    /// Whether this reissues an existing asset, i.e. the blinding nonce is not all-zero.
+    ///
+    /// A [`AssetIssuance::Null`] issuance is neither a new issuance nor a reissuance.
+    pub fn is_reissuance(&self) -> bool {
+        !matches!(self, AssetIssuance::Null(_)) && self.asset_blinding_nonce() != [0; 32]
+    }
+
     pub fn encoded_length(&self) -> usize {
         match self {
             AssetIssuance::Null(inner) => inner.encoded_length(),
@@ -1049,10 +2122,10 @@ impl Encodable for TxIn {
         let mut ret = 0;
         let mut vout = self.previous_output.vout;
         if self.is_pegin {
-            vout |= 1 << 30;
+            vout |= OutPoint::PEGIN_FLAG;
         }
         if self.has_issuance {
-            vout |= 1 << 31;
+            vout |= OutPoint::ISSUANCE_FLAG;
         }
         ret += self.previous_output.txid.consensus_encode(&mut s)?;
         ret += vout.consensus_encode(&mut s)?;
@@ -1079,9 +2152,9 @@ impl Decodable for TxIn {
             is_pegin = false;
             has_issuance = false;
         } else {
-            is_pegin = outp.vout & (1 << 30) != 0;
-            has_issuance = outp.vout & (1 << 31) != 0;
-            outp.vout &= !((1 << 30) | (1 << 31));
+            is_pegin = outp.vout & OutPoint::PEGIN_FLAG != 0;
+            has_issuance = outp.vout & OutPoint::ISSUANCE_FLAG != 0;
+            outp.vout &= !(OutPoint::PEGIN_FLAG | OutPoint::ISSUANCE_FLAG);
         }
         if has_issuance {
             issuance = AssetIssuance::consensus_decode(&mut d)?;
@@ -1167,6 +2240,11 @@ impl ExplicitValue {
     pub fn encoded_length(&self) -> usize {
         9
     }
+
+    /// The prefix byte an explicit value is serialized with.
+    pub fn prefix_byte(&self) -> u8 {
+        1
+    }
 }
 
 impl Encodable for ExplicitValue {
@@ -1190,6 +2268,11 @@ impl ExplicitAsset {
     pub fn encoded_length(&self) -> usize {
         33
     }
+
+    /// The prefix byte an explicit asset is serialized with.
+    pub fn prefix_byte(&self) -> u8 {
+        1
+    }
 }
 
 impl Encodable for ExplicitAsset {
@@ -1219,8 +2302,11 @@ impl ConfidentialTxOut {
     }
 
     pub fn unblind(&self, blinding_key: SecretKey) -> Result<UnblindedTxOut, UnblindError> {
-        let sender_ephemeral_pk = self.nonce.ok_or(UnblindError::MissingNonce)?.commitment();
-        let sender_ephemeral_pk = PublicKey::from_slice(&sender_ephemeral_pk)
+        let sender_ephemeral_pk = self
+            .nonce
+            .and_then(|nonce| nonce.as_confidential())
+            .ok_or(UnblindError::MissingNonce)?
+            .as_public_key()
             .map_err(|_| UnblindError::InvalidPublicKey)?;
 
         let (unblinded_asset, abf, vbf, value_out) = asset_unblind(
@@ -1250,6 +2336,23 @@ pub struct UnblindedTxOut {
     pub value_blinding_factor: ValueBlindingFactor,
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for UnblindedTxOut {
+    fn zeroize(&mut self) {
+        self.value.zeroize();
+        self.asset_blinding_factor.zeroize();
+        self.value_blinding_factor.zeroize();
+    }
+}
+
+/// Scrubs the blinding factors and the value once the unblinded output goes out of scope.
+#[cfg(feature = "zeroize")]
+impl Drop for UnblindedTxOut {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
 #[derive(Debug)]
 pub enum UnblindError {
     MissingNonce,
@@ -1260,7 +2363,7 @@ pub enum UnblindError {
 impl fmt::Display for UnblindError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match self {
-            UnblindError::MissingNonce => write!(f, "no nonce in txout"),
+            UnblindError::MissingNonce => write!(f, "no confidential nonce in txout"),
             UnblindError::InvalidPublicKey => write!(f, "failed to create public key from nonce"),
             UnblindError::Wally => write!(f, "libwally error"),
         }
@@ -1297,12 +2400,16 @@ impl Decodable for ConfidentialTxOut {
         let asset = Decodable::consensus_decode(&mut d)?;
         let value = Decodable::consensus_decode(&mut d)?;
         let buffer = d.fill_buf()?;
+        if buffer.is_empty() {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
 
-        let nonce = if buffer[0] == 0 {
-            d.consume(1); // consume the zero from the buffer
-            None
-        } else {
-            Some(Decodable::consensus_decode(&mut d)?)
+        let nonce = match buffer[0] {
+            0 => {
+                d.consume(1); // consume the zero from the buffer
+                None
+            }
+            _ => Some(Decodable::consensus_decode(&mut d)?),
         };
 
         Ok(ConfidentialTxOut {
@@ -1353,11 +2460,24 @@ impl Decodable for TxOut {
 }
 
 impl Encodable for Transaction {
-    fn consensus_encode<S: io::Write>(&self, mut s: S) -> Result<usize, encode::Error> {
+    fn consensus_encode<S: io::Write>(&self, s: S) -> Result<usize, encode::Error> {
+        self.consensus_encode_with_opts(s, EncodeOpts::default())
+    }
+}
+
+impl Transaction {
+    /// Consensus-encode the transaction like [`Encodable`] does, but according to `opts`.
+    ///
+    /// Without the witness, this is the serialization the txid is the hash of.
+    pub fn consensus_encode_with_opts<S: io::Write>(
+        &self,
+        mut s: S,
+        opts: EncodeOpts,
+    ) -> Result<usize, encode::Error> {
         let mut ret = 0;
         ret += self.version.consensus_encode(&mut s)?;
 
-        let wit_flag = self.has_witness();
+        let wit_flag = opts.include_witness && self.has_witness();
         if wit_flag {
             ret += 1u8.consensus_encode(&mut s)?;
         } else {
@@ -1435,59 +2555,202 @@ impl Decodable for Transaction {
         }
     }
 }
-/// Hashtype of a transaction, encoded in the last byte of a signature
-/// Fixed values so they can be casted as integer types for encoding
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub enum SigHashType {
+
+#[cfg(feature = "base64")]
+impl Transaction {
+    /// Encode the consensus serialization of the transaction as base64.
+    pub fn to_base64(&self) -> String {
+        base64::encode(&encode::serialize(self))
+    }
+
+    /// Decode a transaction from its base64-encoded consensus serialization.
+    pub fn from_base64(s: &str) -> Result<Self, encode::Error> {
+        let bytes = base64::decode(s).map_err(|_| encode::Error::ParseFailed("invalid base64"))?;
+
+        encode::deserialize(&bytes)
+    }
+}
+/// The base mode of a sighash type, i.e. which outputs a signature commits to.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub enum SigHashBase {
     /// 0x1: Sign all outputs
     All = 0x01,
     /// 0x2: Sign no outputs --- anyone can choose the destination
     None = 0x02,
     /// 0x3: Sign the output whose index matches this input's index. If none exists,
-    /// sign the hash `0000000000000000000000000000000000000000000000000000000000000001`.
-    /// (This rule is probably an unintentional C++ism, but it's consensus so we have
-    /// to follow it.)
+    /// sign the all-zero hash instead.
     Single = 0x03,
-    /// 0x81: Sign all outputs but only this input
-    AllPlusAnyoneCanPay = 0x81,
-    /// 0x82: Sign no outputs and only this input
-    NonePlusAnyoneCanPay = 0x82,
-    /// 0x83: Sign one output and only this input (see `Single` for what "one output" means)
-    SinglePlusAnyoneCanPay = 0x83,
+}
+
+/// Hashtype of a transaction, encoded in the last byte of a signature
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub struct SigHashType {
+    /// Which outputs are signed
+    pub base: SigHashBase,
+    /// 0x80: Sign only this input
+    pub anyone_can_pay: bool,
+    /// 0x40: Additionally sign the rangeproofs and surjection proofs of the signed outputs
+    pub rangeproof: bool,
 }
 
 impl SigHashType {
-    /// Break the sighash flag into the "real" sighash flag and the ANYONECANPAY boolean
-    pub(crate) fn split_anyonecanpay_flag(self) -> (SigHashType, bool) {
-        match self {
-            SigHashType::All => (SigHashType::All, false),
-            SigHashType::None => (SigHashType::None, false),
-            SigHashType::Single => (SigHashType::Single, false),
-            SigHashType::AllPlusAnyoneCanPay => (SigHashType::All, true),
-            SigHashType::NonePlusAnyoneCanPay => (SigHashType::None, true),
-            SigHashType::SinglePlusAnyoneCanPay => (SigHashType::Single, true),
+    /// 0x1: Sign all outputs
+    pub const ALL: SigHashType = SigHashType::new(SigHashBase::All);
+    /// 0x2: Sign no outputs --- anyone can choose the destination
+    pub const NONE: SigHashType = SigHashType::new(SigHashBase::None);
+    /// 0x3: Sign the output whose index matches this input's index
+    pub const SINGLE: SigHashType = SigHashType::new(SigHashBase::Single);
+    /// 0x81: Sign all outputs but only this input
+    pub const ALL_PLUS_ANYONECANPAY: SigHashType = SigHashType::ALL.with_anyone_can_pay();
+    /// 0x82: Sign no outputs and only this input
+    pub const NONE_PLUS_ANYONECANPAY: SigHashType = SigHashType::NONE.with_anyone_can_pay();
+    /// 0x83: Sign one output and only this input (see `SINGLE` for what "one output" means)
+    pub const SINGLE_PLUS_ANYONECANPAY: SigHashType = SigHashType::SINGLE.with_anyone_can_pay();
+
+    const ANYONECANPAY_FLAG: u32 = 0x80;
+    const RANGEPROOF_FLAG: u32 = 0x40;
+
+    /// A sighash type with the given base mode and neither flag set.
+    pub const fn new(base: SigHashBase) -> SigHashType {
+        SigHashType {
+            base,
+            anyone_can_pay: false,
+            rangeproof: false,
         }
     }
 
-    /// Reads a 4-byte uint32 as a sighash type
-    pub fn from_u32(n: u32) -> SigHashType {
-        match n & 0x9f {
-            // "real" sighashes
-            0x01 => SigHashType::All,
-            0x02 => SigHashType::None,
-            0x03 => SigHashType::Single,
-            0x81 => SigHashType::AllPlusAnyoneCanPay,
-            0x82 => SigHashType::NonePlusAnyoneCanPay,
-            0x83 => SigHashType::SinglePlusAnyoneCanPay,
-            // catchalls
-            x if x & 0x80 == 0x80 => SigHashType::AllPlusAnyoneCanPay,
-            _ => SigHashType::All,
+    const fn with_anyone_can_pay(self) -> SigHashType {
+        SigHashType {
+            anyone_can_pay: true,
+            ..self
         }
     }
 
-    /// Converts to a u32
+    /// Break the sighash flag into the "real" sighash flag and the ANYONECANPAY boolean
+    pub(crate) fn split_anyonecanpay_flag(self) -> (SigHashType, bool) {
+        (
+            SigHashType {
+                anyone_can_pay: false,
+                ..self
+            },
+            self.anyone_can_pay,
+        )
+    }
+
+    /// Reads a 4-byte uint32 as a sighash type
+    ///
+    /// Like elementsd, any base mode other than `NONE` or `SINGLE` is treated as `ALL`.
+    pub fn from_u32(n: u32) -> SigHashType {
+        let base = match n & 0x1f {
+            0x02 => SigHashBase::None,
+            0x03 => SigHashBase::Single,
+            _ => SigHashBase::All,
+        };
+
+        SigHashType {
+            base,
+            anyone_can_pay: n & Self::ANYONECANPAY_FLAG != 0,
+            rangeproof: n & Self::RANGEPROOF_FLAG != 0,
+        }
+    }
+
+    /// Converts to a u32
     pub fn as_u32(self) -> u32 {
-        self as u32
+        let mut n = self.base as u32;
+        if self.anyone_can_pay {
+            n |= Self::ANYONECANPAY_FLAG;
+        }
+        if self.rangeproof {
+            n |= Self::RANGEPROOF_FLAG;
+        }
+        n
+    }
+}
+
+/// Formats the sighash type like elementsd, e.g. `SINGLE|ANYONECANPAY`.
+impl fmt::Display for SigHashType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.base {
+            SigHashBase::All => f.write_str("ALL")?,
+            SigHashBase::None => f.write_str("NONE")?,
+            SigHashBase::Single => f.write_str("SINGLE")?,
+        }
+        if self.anyone_can_pay {
+            f.write_str("|ANYONECANPAY")?;
+        }
+        if self.rangeproof {
+            f.write_str("|RANGEPROOF")?;
+        }
+        Ok(())
+    }
+}
+
+/// An unknown sighash type name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSigHashTypeError(pub String);
+
+impl fmt::Display for ParseSigHashTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown sighash type: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseSigHashTypeError {}
+
+/// Parses the format of [`Display`](fmt::Display), the flags have to be in the same order.
+impl std::str::FromStr for SigHashType {
+    type Err = ParseSigHashTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('|');
+        let base = match parts.next() {
+            Some("ALL") => SigHashBase::All,
+            Some("NONE") => SigHashBase::None,
+            Some("SINGLE") => SigHashBase::Single,
+            _ => return Err(ParseSigHashTypeError(s.to_owned())),
+        };
+        let mut sighash_type = SigHashType::new(base);
+
+        let mut next = parts.next();
+        if next == Some("ANYONECANPAY") {
+            sighash_type.anyone_can_pay = true;
+            next = parts.next();
+        }
+        if next == Some("RANGEPROOF") {
+            sighash_type.rangeproof = true;
+            next = parts.next();
+        }
+        if next.is_some() {
+            return Err(ParseSigHashTypeError(s.to_owned()));
+        }
+
+        Ok(sighash_type)
+    }
+}
+
+/// Serializes as the name in human-readable formats and as the `u32` otherwise.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SigHashType {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(self)
+        } else {
+            s.serialize_u32(self.as_u32())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SigHashType {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        use serde::{de::Error, Deserialize};
+
+        if d.is_human_readable() {
+            let name = String::deserialize(d)?;
+            name.parse().map_err(D::Error::custom)
+        } else {
+            u32::deserialize(d).map(SigHashType::from_u32)
+        }
     }
 }
 
@@ -1496,8 +2759,80 @@ mod tests {
     use bitcoin::hashes::hex::FromHex;
 
     use super::*;
+    use crate::PubkeyHash;
     use encode::serialize;
 
+    #[test]
+    fn sighash_type_u32_roundtrip() {
+        for &n in &[
+            0x01, 0x02, 0x03, 0x41, 0x42, 0x43, 0x81, 0x82, 0x83, 0xc1, 0xc2, 0xc3,
+        ] {
+            assert_eq!(SigHashType::from_u32(n).as_u32(), n);
+        }
+
+        assert_eq!(SigHashType::from_u32(0x00), SigHashType::ALL);
+        assert_eq!(
+            SigHashType::from_u32(0x84),
+            SigHashType::ALL_PLUS_ANYONECANPAY
+        );
+        assert_eq!(
+            SigHashType::from_u32(0xc3),
+            SigHashType {
+                base: SigHashBase::Single,
+                anyone_can_pay: true,
+                rangeproof: true,
+            }
+        );
+        assert_eq!(
+            SigHashType::NONE_PLUS_ANYONECANPAY.split_anyonecanpay_flag(),
+            (SigHashType::NONE, true)
+        );
+    }
+
+    #[test]
+    fn sighash_type_names() {
+        for &n in &[0x01, 0x02, 0x03, 0x41, 0x81, 0xc2, 0xc3] {
+            let sighash_type = SigHashType::from_u32(n);
+            assert_eq!(
+                sighash_type.to_string().parse::<SigHashType>(),
+                Ok(sighash_type)
+            );
+        }
+
+        assert_eq!(
+            SigHashType::SINGLE_PLUS_ANYONECANPAY.to_string(),
+            "SINGLE|ANYONECANPAY"
+        );
+        assert_eq!(
+            SigHashType::from_u32(0xc1).to_string(),
+            "ALL|ANYONECANPAY|RANGEPROOF"
+        );
+        for name in &[
+            "",
+            "all",
+            "ANYONECANPAY",
+            "ALL|",
+            "ALL|RANGEPROOF|ANYONECANPAY",
+        ] {
+            assert_eq!(
+                name.parse::<SigHashType>(),
+                Err(ParseSigHashTypeError(name.to_string()))
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn sighash_type_serde() {
+        let json = serde_json::to_string(&SigHashType::NONE_PLUS_ANYONECANPAY).unwrap();
+        assert_eq!(json, r#""NONE|ANYONECANPAY""#);
+        assert_eq!(
+            serde_json::from_str::<SigHashType>(&json).unwrap(),
+            SigHashType::NONE_PLUS_ANYONECANPAY
+        );
+        assert!(serde_json::from_str::<SigHashType>(r#""EVERYTHING""#).is_err());
+    }
+
     #[test]
     fn outpoint() {
         let txid = "d0a5c455ea7221dead9513596d2f97c09943bad81a386fe61a14a6cda060e422";
@@ -1510,6 +2845,66 @@ mod tests {
         assert_eq!(op, Some(expected));
     }
 
+    #[test]
+    fn consensus_encode_with_opts() {
+        let mut tx: Transaction = hex_deserialize!(
+            "020000000001eb04b68e9a26d116046c76e8ff47332fb71dda90ff4bef5370f2\
+             5226d3bc09fc0000000000feffffff0201230f4f5d4b7c6fa845806ee4f67713\
+             459e1b69e8e60fcee2e4940c7a0d5de1b20100000002540bd71c001976a91448\
+             633e2c0ee9495dd3f9c43732c47f4702a362c888ac01230f4f5d4b7c6fa84580\
+             6ee4f67713459e1b69e8e60fcee2e4940c7a0d5de1b2010000000000000ce400\
+             0000000000"
+        );
+        let stripped = serialize(&tx);
+        tx.input[0].witness.script_witness = vec![vec![0xab; 3]];
+
+        let encode = |include_witness| {
+            let mut data = Vec::new();
+            let len = tx
+                .consensus_encode_with_opts(&mut data, EncodeOpts { include_witness })
+                .unwrap();
+            assert_eq!(len, data.len());
+            data
+        };
+
+        let with_witness = encode(true);
+        assert_eq!(with_witness, serialize(&tx));
+        assert_eq!(with_witness[4], 1);
+        assert_eq!(
+            EncodeOpts::default(),
+            EncodeOpts {
+                include_witness: true
+            }
+        );
+
+        let without_witness = encode(false);
+        assert_eq!(without_witness, stripped);
+        assert_eq!(Txid::hash(&without_witness), tx.txid());
+        assert_ne!(tx.txid().as_hash(), tx.wtxid().as_hash());
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn transaction_base64_roundtrip() {
+        let tx: Transaction = hex_deserialize!(
+            "020000000001eb04b68e9a26d116046c76e8ff47332fb71dda90ff4bef5370f2\
+             5226d3bc09fc0000000000feffffff0201230f4f5d4b7c6fa845806ee4f67713\
+             459e1b69e8e60fcee2e4940c7a0d5de1b20100000002540bd71c001976a91448\
+             633e2c0ee9495dd3f9c43732c47f4702a362c888ac01230f4f5d4b7c6fa84580\
+             6ee4f67713459e1b69e8e60fcee2e4940c7a0d5de1b2010000000000000ce400\
+             0000000000"
+        );
+        let encoded = tx.to_base64();
+
+        assert_eq!(encoded, base64::encode(&serialize(&tx)));
+        assert_eq!(Transaction::from_base64(&encoded).unwrap(), tx);
+        assert!(matches!(
+            Transaction::from_base64("not base64!"),
+            Err(encode::Error::ParseFailed("invalid base64"))
+        ));
+        assert!(Transaction::from_base64(&base64::encode(&[0x02, 0x00])).is_err());
+    }
+
     #[test]
     fn transaction() {
         // Simple transaction with explicit input (no scriptsig/witness) and explicit outputs
@@ -1552,6 +2947,12 @@ mod tests {
         assert_eq!(tx.fee_in(fee_asset), 3300);
         assert_eq!(tx.all_fees()[&fee_asset], 3300);
 
+        let pretty = tx.pretty();
+        assert!(pretty.starts_with(
+            "txid: 758f784bdfa89b62c8b882542afb46074d3851a6da997199bcfb7cc6daed3cf2\n"
+        ));
+        assert!(pretty.contains(&format!("  #1 asset: {} value: 3300 (fee)\n", fee_asset)));
+
         // CT transaction with explicit input (with script witness) and confidential outputs
         let tx: Transaction = hex_deserialize!(
             "020000000101f23ceddac67cfbbc997199daa651384d0746fb2a5482b8c8629b\
@@ -1752,6 +3153,53 @@ mod tests {
         );
         assert_eq!(tx.get_size(), serialize(&tx).len());
         assert_eq!(tx.get_weight(), 7296);
+        assert_eq!(
+            tx.overhead_weight()
+                + tx.input.iter().map(TxIn::weight).sum::<usize>()
+                + tx.output.iter().map(TxOut::weight).sum::<usize>(),
+            tx.get_weight()
+        );
+        assert_eq!(tx.overhead_weight(), 44);
+        assert_eq!(tx.discount_weight(), 1374);
+        assert_eq!(tx.discount_vsize(), 344);
+
+        // the commitments are discounted even if none of the outputs carry proofs
+        let mut stripped = tx.clone();
+        for output in stripped.output.iter_mut() {
+            if let TxOut::Confidential(confidential) = output {
+                confidential.witness = TxOutWitness::default();
+            }
+        }
+        for input in stripped.input.iter_mut() {
+            input.witness = TxInWitness::default();
+        }
+        assert!(!stripped.has_witness());
+        assert_eq!(
+            stripped.discount_weight(),
+            stripped.get_weight() - 2 * (33 - 9) * 4 - 2 * (33 - 1) * 4
+        );
+        assert_eq!(tx.minimum_fee(0.1, true), 35);
+        assert_eq!(tx.minimum_fee(1.0, true), 344);
+        assert_eq!(tx.minimum_fee(1.1, true), 379);
+        assert_eq!(tx.minimum_fee(0.1, false), 183);
+        assert_eq!(tx.minimum_fee(1.0, false), 1824);
+        assert_eq!(tx.minimum_fee(0.0, false), 0);
+        assert!(tx.meets_min_relay_fee(35, 0.1));
+        assert!(!tx.meets_min_relay_fee(34, 0.1));
+        assert!(tx.meets_min_relay_fee(0, 0.0));
+        assert_eq!(tx.minimum_fee(1e18, true), u64::MAX);
+        assert_eq!(tx.minimum_fee(f64::INFINITY, true), u64::MAX);
+        assert!(!tx.meets_min_relay_fee(u64::MAX - 1, f64::INFINITY));
+
+        let stripped = tx.without_witness_proofs();
+        assert_eq!(stripped.txid(), tx.txid());
+        assert_eq!(stripped.input[0].witness, tx.input[0].witness);
+        assert!(stripped.output.iter().all(|o| !o.has_witness()));
+        assert_eq!(stripped.get_size(), serialize(&stripped).len());
+        assert!(stripped.get_weight() < tx.get_weight());
+        assert_eq!(tx.total_proof_bytes(), 5470);
+        assert_eq!(stripped.total_proof_bytes(), 0);
+
         assert_eq!(tx.input.len(), 1);
         assert_eq!(tx.input[0].is_coinbase(), false);
         assert_eq!(tx.is_coinbase(), false);
@@ -1775,6 +3223,28 @@ mod tests {
         assert_eq!(tx.fee_in(fee_asset), 36480);
         assert_eq!(tx.all_fees()[&fee_asset], 36480);
 
+        assert_eq!(
+            tx.pretty(),
+            "txid: d606b563122409191e3b114a41d5611332dc58237ad5d2dccded302664fd56c4\n\
+             version: 2\n\
+             lock_time: 0\n\
+             inputs:\n  \
+             #0 prevout: 758f784bdfa89b62c8b882542afb46074d3851a6da997199bcfb7cc6daed3cf2:0\n     \
+             script_sig: OP_PUSHBYTES_72 3045022100e0feb3e2f292000d67e24b821d87c9532230dac1de428d6a00\
+             68c9f416583abf02200e76f072788dd411b2327267cd91c6b1659809598cd4fae35be475efe1e4bbad01 \
+             OP_PUSHBYTES_33 0201e15c23c021652d07c1557b607ea0379fca0462aca840d6c33c4d4927524547\n\
+             outputs:\n  \
+             #0 asset: confidential(0b60424a..) value: confidential(081c4f21..)\n     \
+             script_pubkey: OP_DUP OP_HASH160 OP_PUSHBYTES_20 \
+             8bb6c4d5814d43fefb9e330575e326632136389c OP_EQUALVERIFY OP_CHECKSIG\n  \
+             #1 asset: confidential(0bd436b0..) value: confidential(0923899f..)\n     \
+             script_pubkey: OP_DUP OP_HASH160 OP_PUSHBYTES_20 \
+             8c7ab6e0fca387d03643d4846f708bf39d47c1e9 OP_EQUALVERIFY OP_CHECKSIG\n  \
+             #2 asset: b2e15d0d7a0c94e4e2ce0fe6e8691b9e451377f6e46e8045a86f7c4b5d4f0f23 \
+             value: 36480 (fee)\n     \
+             script_pubkey: \n"
+        );
+
         // Coinbase tx
         let tx: Transaction = hex_deserialize!(
             "0200000001010000000000000000000000000000000000000000000000000000\
@@ -1800,6 +3270,13 @@ mod tests {
         assert_eq!(tx.input[0].is_coinbase(), true);
         assert_eq!(!tx.input[0].is_pegin(), true);
         assert_eq!(tx.input[0].pegin_data(), None);
+        assert_eq!(tx.input[0].pegin_witness(), None);
+
+        let cached = CachedTxid::new(&tx);
+        assert_eq!(cached.txid(), tx.txid());
+        assert_eq!(cached.txid(), tx.txid());
+        assert_eq!(cached.wtxid(), tx.wtxid());
+        assert_eq!(cached.transaction(), &tx);
         assert_eq!(tx.is_coinbase(), true);
 
         assert_eq!(tx.output.len(), 2);
@@ -1927,6 +3404,19 @@ mod tests {
             .unwrap();
         assert_eq!(tx.fee_in(fee_asset), 6260);
         assert_eq!(tx.all_fees()[&fee_asset], 6260);
+
+        let pegin_witness = tx.input[0].pegin_witness().unwrap();
+        let encoded = serialize(&pegin_witness);
+        assert_eq!(encoded, serialize(&tx.input[0].witness.pegin_witness));
+        assert_eq!(
+            encode::deserialize::<PeginWitness>(&encoded).unwrap(),
+            pegin_witness
+        );
+        assert_eq!(
+            pegin_witness.into_stack(),
+            tx.input[0].witness.pegin_witness
+        );
+        assert!(encode::deserialize::<PeginWitness>(&serialize(&vec![vec![0u8]])).is_err());
     }
 
     #[test]
@@ -2333,6 +3823,1041 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fees_in_non_policy_asset() {
+        let policy_asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let other_asset = AssetId::from_slice(&[2; 32]).unwrap();
+        let script_pubkey = hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1");
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![],
+            output: vec![
+                TxOut::new_explicit(policy_asset, 10_000, script_pubkey.clone()),
+                TxOut::new_fee(other_asset, 300),
+                TxOut::new_explicit(other_asset, 5_000, script_pubkey),
+                TxOut::new_fee(other_asset, 200),
+            ],
+        };
+
+        // a fee output is any explicit output with an empty scriptPubKey, whatever its asset
+        assert_eq!(tx.fee_in(policy_asset), 0);
+        assert_eq!(tx.fee_in(other_asset), 500);
+
+        let fees = tx.all_fees();
+        assert_eq!(fees.len(), 1);
+        assert_eq!(fees[&other_asset], 500);
+    }
+
+    #[test]
+    fn total_sent() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let other_asset = AssetId::from_slice(&[2; 32]).unwrap();
+        let script_pubkey = hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1");
+
+        let mut tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![],
+            output: vec![
+                TxOut::new_explicit(asset, 10_000, script_pubkey.clone()),
+                TxOut::new_explicit(other_asset, 5_000, script_pubkey.clone()),
+                TxOut::new_explicit(asset, 2_500, hex_script!("6a")),
+                TxOut::new_fee(asset, 300),
+            ],
+        };
+        assert_eq!(tx.total_sent(asset), Some(12_500));
+        assert_eq!(tx.total_sent(other_asset), Some(5_000));
+        assert_eq!(
+            tx.total_sent(AssetId::from_slice(&[3; 32]).unwrap()),
+            Some(0)
+        );
+
+        tx.output
+            .push(TxOut::new_explicit(asset, u64::MAX, script_pubkey.clone()));
+        assert_eq!(tx.total_sent(asset), Some(u64::MAX));
+
+        tx.output.push(TxOut::Confidential(ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[3; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[4; 32]).unwrap(),
+            nonce: None,
+            script_pubkey,
+            witness: TxOutWitness::default(),
+        }));
+        assert_eq!(tx.total_sent(other_asset), None);
+    }
+
+    #[test]
+    fn burn_outputs_are_not_fees() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let burn_script = hex_script!("6a");
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![],
+            output: vec![
+                TxOut::new_fee(asset, 300),
+                TxOut::new_explicit(asset, 5_000, burn_script),
+                TxOut::new_fee(asset, 200),
+            ],
+        };
+
+        assert!(tx.output[1].script_pubkey().is_provably_unspendable());
+        assert!(!tx.output[1].is_fee());
+        assert_eq!(tx.fee_in(asset), 500);
+        assert_eq!(tx.all_fees()[&asset], 500);
+    }
+
+    #[test]
+    fn txin_flags_roundtrip() {
+        let outpoint = OutPoint::new(Txid::from_slice(&[1; 32]).unwrap(), 5);
+        let flagged_input = |is_pegin, has_issuance| TxIn {
+            previous_output: outpoint,
+            is_pegin,
+            has_issuance,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::default(),
+            witness: TxInWitness::default(),
+        };
+
+        for &(is_pegin, has_issuance, vout) in &[
+            (false, false, 5),
+            (true, false, 5 | OutPoint::PEGIN_FLAG),
+            (false, true, 5 | OutPoint::ISSUANCE_FLAG),
+            (
+                true,
+                true,
+                5 | OutPoint::PEGIN_FLAG | OutPoint::ISSUANCE_FLAG,
+            ),
+        ] {
+            let input = flagged_input(is_pegin, has_issuance);
+            let encoded = serialize(&input);
+            assert_eq!(encoded[32..36], vout.to_le_bytes());
+
+            let decoded: TxIn = encode::deserialize(&encoded).unwrap();
+            assert_eq!(decoded, input);
+            assert_eq!(decoded.previous_output.vout, 5);
+        }
+    }
+
+    #[test]
+    fn fully_explicit() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let script_pubkey = hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1");
+        let confidential_output = TxOut::Confidential(ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[2; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[3; 32]).unwrap(),
+            nonce: None,
+            script_pubkey: script_pubkey.clone(),
+            witness: TxOutWitness::default(),
+        });
+        let input = TxIn {
+            previous_output: OutPoint::new(Txid::from_slice(&[4; 32]).unwrap(), 0),
+            is_pegin: false,
+            has_issuance: false,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::Null(Default::default()),
+            witness: TxInWitness::default(),
+        };
+
+        let mut tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![input],
+            output: vec![
+                TxOut::new_explicit(asset, 1_000, script_pubkey),
+                TxOut::new_fee(asset, 100),
+            ],
+        };
+        assert!(tx.is_fully_explicit());
+
+        tx.input[0].has_issuance = true;
+        tx.input[0].asset_issuance = AssetIssuance::Explicit(ExplicitAssetIssuance {
+            asset_blinding_nonce: [0; 32],
+            asset_entropy: [5; 32],
+            amount: ExplicitValue(10),
+            inflation_keys: ExplicitValue(1),
+        });
+        assert!(tx.is_fully_explicit());
+
+        tx.input[0].witness.amount_rangeproof = vec![6; 10];
+        assert!(!tx.is_fully_explicit());
+        tx.input[0].witness.amount_rangeproof.clear();
+
+        tx.input[0].asset_issuance = AssetIssuance::Confidential(ConfidentialAssetIssuance {
+            asset_blinding_nonce: [0; 32],
+            asset_entropy: [5; 32],
+            amount: ValueCommitment::from_commitment(0x09, &[7; 32]).unwrap(),
+            inflation_keys: None,
+        });
+        assert!(!tx.is_fully_explicit());
+        tx.input[0].asset_issuance = AssetIssuance::Null(Default::default());
+
+        tx.output.push(confidential_output);
+        assert!(!tx.is_fully_explicit());
+    }
+
+    #[test]
+    fn zero_explicit_value() {
+        let value = ExplicitValue(0);
+        let encoded = serialize(&value);
+        assert_eq!(encoded, vec![0x01, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(encoded.len(), value.encoded_length());
+        assert_eq!(
+            encode::deserialize::<ExplicitValue>(&encoded).unwrap(),
+            value
+        );
+
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        for script_pubkey in vec![
+            hex_script!("6a0b68656c6c6f20776f726c64"),
+            hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1"),
+        ] {
+            let output = TxOut::new_explicit(asset, 0, script_pubkey);
+            let encoded = serialize(&output);
+            assert_eq!(&encoded[33..42], &[0x01, 0, 0, 0, 0, 0, 0, 0, 0]);
+            assert_eq!(encoded.len(), output.encoded_length());
+
+            let decoded = encode::deserialize::<TxOut>(&encoded).unwrap();
+            assert_eq!(decoded, output);
+            assert_eq!(decoded.explicit_value(), Some(0));
+            assert!(!decoded.is_null());
+        }
+    }
+
+    #[test]
+    fn txout_is_dust() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let p2wpkh = hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1");
+        let p2pkh = hex_script!("76a91448633e2c0ee9495dd3f9c43732c47f4702a362c888ac");
+
+        // 66 bytes for the output and 67 for the input spending it
+        assert!(TxOut::new_explicit(asset, 398, p2wpkh.clone()).is_dust(3000));
+        assert!(!TxOut::new_explicit(asset, 399, p2wpkh.clone()).is_dust(3000));
+        // 69 bytes for the output and 148 for the input spending it
+        assert!(TxOut::new_explicit(asset, 650, p2pkh.clone()).is_dust(3000));
+        assert!(!TxOut::new_explicit(asset, 651, p2pkh).is_dust(3000));
+
+        assert!(!TxOut::new_explicit(asset, 0, hex_script!("6a")).is_dust(3000));
+        assert!(!TxOut::new_explicit(asset, 0, p2wpkh.clone()).is_dust(0));
+        assert!(TxOut::new_explicit(asset, 0, p2wpkh.clone()).is_dust(3000));
+        assert!(TxOut::new_explicit(asset, 0, p2wpkh.clone()).is_dust(1));
+        assert!(TxOut::new_explicit(asset, u64::MAX, p2wpkh.clone()).is_dust(u64::MAX));
+
+        let confidential = TxOut::Confidential(ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[2; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[3; 32]).unwrap(),
+            nonce: None,
+            script_pubkey: p2wpkh,
+            witness: TxOutWitness::default(),
+        });
+        assert!(!confidential.is_dust(3000));
+    }
+
+    #[test]
+    fn output_scripts() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let watched = hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1");
+        let other = hex_script!("76a91448633e2c0ee9495dd3f9c43732c47f4702a362c888ac");
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![],
+            output: vec![
+                TxOut::new_explicit(asset, 1_000, watched.clone()),
+                TxOut::new_explicit(asset, 2_000, other.clone()),
+                TxOut::new_explicit(asset, 3_000, watched.clone()),
+                TxOut::new_fee(asset, 100),
+            ],
+        };
+
+        assert_eq!(
+            tx.output_scripts().collect::<Vec<_>>(),
+            vec![
+                (0, &watched),
+                (1, &other),
+                (2, &watched),
+                (3, &Script::new())
+            ]
+        );
+        assert_eq!(tx.find_outputs_to(&watched), vec![0, 2]);
+        assert_eq!(tx.find_outputs_to(&other), vec![1]);
+        assert!(tx.find_outputs_to(&hex_script!("6a")).is_empty());
+    }
+
+    #[test]
+    fn output_merkle_root() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let outputs = (0..3)
+            .map(|value| TxOut::new_explicit(asset, value, Script::new()))
+            .collect::<Vec<_>>();
+        let leaf = |output: &TxOut| sha256d::Hash::hash(&serialize(output));
+        let node = |left: sha256d::Hash, right: sha256d::Hash| {
+            sha256d::Hash::hash(&[&left[..], &right[..]].concat())
+        };
+
+        let mut tx = Transaction::default();
+        assert_eq!(tx.output_merkle_root(), None);
+
+        tx.output = outputs[..1].to_vec();
+        assert_eq!(tx.output_merkle_root(), Some(leaf(&outputs[0])));
+
+        tx.output = outputs.clone();
+        assert_eq!(
+            tx.output_merkle_root(),
+            Some(node(
+                node(leaf(&outputs[0]), leaf(&outputs[1])),
+                node(leaf(&outputs[2]), leaf(&outputs[2]))
+            ))
+        );
+    }
+
+    #[test]
+    fn issuance_classification() {
+        let new_issuance = AssetIssuance::Explicit(ExplicitAssetIssuance {
+            asset_blinding_nonce: [0; 32],
+            asset_entropy: [1; 32],
+            amount: ExplicitValue(10),
+            inflation_keys: ExplicitValue(1),
+        });
+        assert!(new_issuance.is_new_issuance());
+        assert!(!new_issuance.is_reissuance());
+        assert_eq!(new_issuance.asset_blinding_nonce(), [0; 32]);
+        assert_eq!(new_issuance.asset_entropy(), [1; 32]);
+
+        let reissuance = AssetIssuance::Confidential(ConfidentialAssetIssuance {
+            asset_blinding_nonce: [2; 32],
+            asset_entropy: [3; 32],
+            amount: ValueCommitment::from_commitment(0x09, &[4; 32]).unwrap(),
+            inflation_keys: None,
+        });
+        assert!(!reissuance.is_new_issuance());
+        assert!(reissuance.is_reissuance());
+        assert_eq!(reissuance.asset_blinding_nonce(), [2; 32]);
+        assert_eq!(reissuance.asset_entropy(), [3; 32]);
+
+        let null = AssetIssuance::default();
+        assert!(!null.is_new_issuance());
+        assert!(!null.is_reissuance());
+    }
+
+    #[test]
+    fn verify_issuances() {
+        // the issuance of the example in Elements Core 0.17, see `issuance::test`
+        let asset =
+            AssetId::from_hex("dcd60818d863b5c026c40b2bc3ba6fdaf5018bcc8606c18adf7db4da0bcd8533")
+                .unwrap();
+        let token =
+            AssetId::from_hex("c1adb114f4f87d33bf9ce90dd4f9ca523dd414d6cd010a7917903e2009689530")
+                .unwrap();
+        let entropy: AssetEntropy =
+            "746f447f691323502cad2ef646f932613d37a83aeaa2133185b316648df4b70a"
+                .parse()
+                .unwrap();
+        let issuance_input = |asset_blinding_nonce, asset_entropy, amount, inflation_keys| TxIn {
+            previous_output: "05a047c98e82a848dee94efcf32462b065198bebf2404d201ba2e06db30b28f4:0"
+                .parse()
+                .unwrap(),
+            is_pegin: false,
+            has_issuance: true,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::Explicit(ExplicitAssetIssuance {
+                asset_blinding_nonce,
+                asset_entropy,
+                amount: ExplicitValue(amount),
+                inflation_keys: ExplicitValue(inflation_keys),
+            }),
+            witness: TxInWitness::default(),
+        };
+
+        let mut tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![issuance_input([0; 32], [0; 32], 100, 1)],
+            output: vec![
+                TxOut::new_explicit(asset, 100, Script::new()),
+                TxOut::new_explicit(token, 1, Script::new()),
+            ],
+        };
+        assert_eq!(tx.verify_issuances(), Ok(()));
+
+        tx.output.pop();
+        assert_eq!(
+            tx.verify_issuances(),
+            Err(MissingIssuedAsset {
+                input_index: 0,
+                asset: token
+            })
+        );
+
+        tx.input = vec![issuance_input([0; 32], [0; 32], 100, 0)];
+        assert_eq!(tx.verify_issuances(), Ok(()));
+
+        tx.input = vec![issuance_input([1; 32], entropy.into_inner(), 5, 0)];
+        assert_eq!(tx.verify_issuances(), Ok(()));
+
+        tx.output = vec![TxOut::new_explicit(token, 1, Script::new())];
+        assert_eq!(
+            tx.verify_issuances(),
+            Err(MissingIssuedAsset {
+                input_index: 0,
+                asset
+            })
+        );
+
+        tx.output.push(TxOut::Confidential(ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[3; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[4; 32]).unwrap(),
+            nonce: None,
+            script_pubkey: Script::new(),
+            witness: TxOutWitness::default(),
+        }));
+        assert_eq!(tx.verify_issuances(), Ok(()));
+    }
+
+    #[test]
+    fn assets() {
+        // the issuance of the example in Elements Core 0.17, see `issuance::test`
+        let asset =
+            AssetId::from_hex("dcd60818d863b5c026c40b2bc3ba6fdaf5018bcc8606c18adf7db4da0bcd8533")
+                .unwrap();
+        let token =
+            AssetId::from_hex("c1adb114f4f87d33bf9ce90dd4f9ca523dd414d6cd010a7917903e2009689530")
+                .unwrap();
+        let fee_asset = AssetId::from_slice(&[1; 32]).unwrap();
+
+        let mut tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output:
+                    "05a047c98e82a848dee94efcf32462b065198bebf2404d201ba2e06db30b28f4:0"
+                        .parse()
+                        .unwrap(),
+                is_pegin: false,
+                has_issuance: true,
+                script_sig: Script::new(),
+                sequence: 0xffff_ffff,
+                asset_issuance: AssetIssuance::Explicit(ExplicitAssetIssuance {
+                    asset_blinding_nonce: [0; 32],
+                    asset_entropy: [0; 32],
+                    amount: ExplicitValue(100),
+                    inflation_keys: ExplicitValue(1),
+                }),
+                witness: TxInWitness::default(),
+            }],
+            output: vec![TxOut::new_fee(fee_asset, 100)],
+        };
+        assert_eq!(
+            tx.assets(),
+            (vec![asset, token, fee_asset].into_iter().collect(), false)
+        );
+
+        tx.input[0].has_issuance = false;
+        tx.output
+            .push(TxOut::new_explicit(fee_asset, 50, Script::new()));
+        tx.output.push(TxOut::Confidential(ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[3; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[4; 32]).unwrap(),
+            nonce: None,
+            script_pubkey: Script::new(),
+            witness: TxOutWitness::default(),
+        }));
+        assert_eq!(tx.assets(), (vec![fee_asset].into_iter().collect(), true));
+    }
+
+    #[test]
+    fn issued_amounts() {
+        let input = |has_issuance, asset_issuance| TxIn {
+            previous_output: OutPoint::default(),
+            is_pegin: false,
+            has_issuance,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance,
+            witness: TxInWitness::default(),
+        };
+        let explicit = AssetIssuance::Explicit(ExplicitAssetIssuance {
+            asset_blinding_nonce: [0; 32],
+            asset_entropy: [1; 32],
+            amount: ExplicitValue(10),
+            inflation_keys: ExplicitValue(1),
+        });
+        let confidential = AssetIssuance::Confidential(ConfidentialAssetIssuance {
+            asset_blinding_nonce: [0; 32],
+            asset_entropy: [1; 32],
+            amount: ValueCommitment::from_commitment(0x09, &[4; 32]).unwrap(),
+            inflation_keys: Some(ValueCommitment::from_commitment(0x08, &[5; 32]).unwrap()),
+        });
+
+        let explicit_input = input(true, explicit);
+        assert_eq!(explicit_input.issued_amount(), Some(10));
+        assert_eq!(explicit_input.inflation_keys_amount(), Some(1));
+
+        for input in &[
+            input(true, confidential),
+            input(false, AssetIssuance::default()),
+            input(false, explicit),
+        ] {
+            assert_eq!(input.issued_amount(), None);
+            assert_eq!(input.inflation_keys_amount(), None);
+        }
+    }
+
+    #[test]
+    fn signable_inputs() {
+        let secp = Secp256k1::new();
+        let key = |byte| bitcoin::PublicKey {
+            compressed: true,
+            key: bitcoin::secp256k1::PublicKey::from_secret_key(
+                &secp,
+                &SecretKey::from_slice(&[byte; 32]).unwrap(),
+            ),
+        };
+        let pubkey = key(1);
+        let p2wpkh = Script::new_v0_wpkh(&WPubkeyHash::hash(&pubkey.to_bytes()));
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let spent = |script_pubkey: Script| TxOut::new_explicit(asset, 1_000, script_pubkey);
+        let input = TxIn {
+            previous_output: OutPoint::default(),
+            is_pegin: false,
+            has_issuance: false,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::default(),
+            witness: TxInWitness::default(),
+        };
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![input; 4],
+            output: vec![],
+        };
+
+        let spent_outputs = vec![
+            spent(p2wpkh.clone()),
+            spent(Script::new_v0_wpkh(&WPubkeyHash::hash(&key(2).to_bytes()))),
+            spent(p2wpkh.to_p2sh()),
+            spent(Script::new_p2pkh(&PubkeyHash::hash(&pubkey.to_bytes()))),
+            spent(p2wpkh.clone()),
+        ];
+
+        assert_eq!(tx.signable_inputs(&pubkey, &spent_outputs), vec![0, 2]);
+        assert!(tx.signable_inputs(&key(2), &spent_outputs[2..]).is_empty());
+        assert!(tx
+            .signable_inputs(
+                &bitcoin::PublicKey {
+                    compressed: false,
+                    ..pubkey
+                },
+                &spent_outputs
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn unblind_outputs() {
+        let secp = Secp256k1::new();
+        let rng = &mut bitcoin::secp256k1::rand::thread_rng();
+        let master_blinding_key = MasterBlindingKey::new(&[7; 32]);
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+
+        let address = |byte, master_blinding_key: &MasterBlindingKey| {
+            let pk = bitcoin::PublicKey {
+                compressed: true,
+                key: bitcoin::secp256k1::PublicKey::from_secret_key(
+                    &secp,
+                    &SecretKey::from_slice(&[byte; 32]).unwrap(),
+                ),
+            };
+            let mut address = Address::p2wpkh(&pk, None, &crate::AddressParams::ELEMENTS);
+            let blinding_key = master_blinding_key.derive_blinding_key(&address.script_pubkey());
+            address.blinding_pubkey = Some(bitcoin::secp256k1::PublicKey::from_secret_key(
+                &secp,
+                &blinding_key,
+            ));
+            address
+        };
+
+        let abf = AssetBlindingFactor::new(rng);
+        let vbf = ValueBlindingFactor::random(rng);
+        let inputs = [(asset, 10_000, AssetCommitment::new(asset, abf), abf, vbf)];
+        let mut confidential_output = |address| {
+            TxOut::new_not_last_confidential(rng, &secp, 9_000, address, asset, &inputs)
+                .unwrap()
+                .0
+        };
+
+        let ours = confidential_output(address(1, &master_blinding_key));
+        let theirs = confidential_output(address(2, &MasterBlindingKey::new(&[8; 32])));
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![],
+            output: vec![
+                theirs,
+                ours,
+                TxOut::new_explicit(asset, 900, Script::new()),
+                TxOut::new_fee(asset, 100),
+            ],
+        };
+
+        let unblinded = tx.unblind_outputs(&master_blinding_key);
+        assert_eq!(unblinded.len(), 4);
+        assert!(unblinded[0].is_none());
+        let ours = unblinded[1].as_ref().unwrap();
+        assert_eq!(ours.asset, asset);
+        assert_eq!(ours.value, 9_000);
+        assert!(unblinded[2].is_none());
+        assert!(unblinded[3].is_none());
+    }
+
+    #[test]
+    fn reblind() {
+        let secp = Secp256k1::new();
+        let rng = &mut bitcoin::secp256k1::rand::thread_rng();
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let blinding_sk = SecretKey::from_slice(&[3; 32]).unwrap();
+        let pk = bitcoin::PublicKey {
+            compressed: true,
+            key: bitcoin::secp256k1::PublicKey::from_secret_key(
+                &secp,
+                &SecretKey::from_slice(&[2; 32]).unwrap(),
+            ),
+        };
+        let address = Address::p2wpkh(
+            &pk,
+            Some(bitcoin::secp256k1::PublicKey::from_secret_key(
+                &secp,
+                &blinding_sk,
+            )),
+            &crate::AddressParams::ELEMENTS,
+        );
+
+        let abf = AssetBlindingFactor::new(rng);
+        let vbf = ValueBlindingFactor::random(rng);
+        let inputs = [(asset, 10_000, AssetCommitment::new(asset, abf), abf, vbf)];
+        let (original, _, _) =
+            TxOut::new_not_last_confidential(rng, &secp, 9_000, address.clone(), asset, &inputs)
+                .unwrap();
+        let secrets = original
+            .as_confidential()
+            .unwrap()
+            .unblind(blinding_sk)
+            .unwrap();
+
+        let new_abf = AssetBlindingFactor::new(rng);
+        let new_vbf = ValueBlindingFactor::random(rng);
+        let reblinded =
+            TxOut::reblind(rng, &secp, &secrets, address, new_abf, new_vbf, &inputs).unwrap();
+        let reblinded = reblinded.as_confidential().unwrap();
+
+        assert_ne!(reblinded.asset, original.as_confidential().unwrap().asset);
+        assert!(reblinded.asset.verify(asset, new_abf));
+        assert_eq!(
+            reblinded.value,
+            ValueCommitment::new(9_000, reblinded.asset, new_vbf)
+        );
+
+        let unblinded = reblinded.unblind(blinding_sk).unwrap();
+        assert_eq!(unblinded.asset, asset);
+        assert_eq!(unblinded.value, 9_000);
+        assert_eq!(unblinded.asset_blinding_factor, new_abf);
+        assert_eq!(unblinded.value_blinding_factor, new_vbf);
+    }
+
+    #[test]
+    fn builder_methods() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let input = |vout| TxIn {
+            previous_output: OutPoint::new(Txid::from_slice(&[2; 32]).unwrap(), vout),
+            is_pegin: false,
+            has_issuance: false,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::default(),
+            witness: TxInWitness::default(),
+        };
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![input(0)],
+            output: vec![TxOut::new_fee(asset, 100)],
+        };
+        let original = tx.clone();
+
+        let replaced = tx
+            .clone()
+            .with_input(0, input(1))
+            .with_output(0, TxOut::new_fee(asset, 200));
+        assert_eq!(tx, original);
+        assert_eq!(replaced.input, vec![input(1)]);
+        assert_eq!(replaced.output, vec![TxOut::new_fee(asset, 200)]);
+        assert_ne!(replaced.txid(), tx.txid());
+
+        let extended = tx
+            .clone()
+            .push_input(input(1))
+            .push_output(TxOut::new_explicit(asset, 50, Script::new()));
+        assert_eq!(tx, original);
+        assert_eq!(extended.input, vec![input(0), input(1)]);
+        assert_eq!(
+            extended.output,
+            vec![
+                TxOut::new_fee(asset, 100),
+                TxOut::new_explicit(asset, 50, Script::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_inputs() {
+        let input = |vout| TxIn {
+            previous_output: OutPoint::new(Txid::from_slice(&[2; 32]).unwrap(), vout),
+            is_pegin: false,
+            has_issuance: false,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::default(),
+            witness: TxInWitness::default(),
+        };
+
+        let mut tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![input(0), input(1), input(2)],
+            output: vec![],
+        };
+        assert!(!tx.has_duplicate_inputs());
+        assert!(!Transaction::default().has_duplicate_inputs());
+
+        let mut duplicate = input(0);
+        duplicate.sequence = 0;
+        tx.input.push(duplicate);
+        assert!(tx.has_duplicate_inputs());
+    }
+
+    #[test]
+    fn input_and_output_tallies() {
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let input = |vout, is_pegin, has_issuance| TxIn {
+            previous_output: OutPoint::new(Txid::from_slice(&[2; 32]).unwrap(), vout),
+            is_pegin,
+            has_issuance,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::default(),
+            witness: TxInWitness::default(),
+        };
+        let confidential_output = TxOut::Confidential(ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[3; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[4; 32]).unwrap(),
+            nonce: None,
+            script_pubkey: Script::new(),
+            witness: TxOutWitness::default(),
+        });
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![
+                input(0, false, false),
+                input(1, true, false),
+                input(2, false, true),
+                input(3, true, true),
+            ],
+            output: vec![
+                confidential_output.clone(),
+                TxOut::new_explicit(asset, 1_000, Script::new()),
+                confidential_output,
+                TxOut::new_fee(asset, 100),
+            ],
+        };
+
+        assert_eq!(tx.issuance_count(), 2);
+        assert_eq!(tx.pegin_count(), 2);
+        assert_eq!(tx.confidential_output_count(), 2);
+        assert_eq!(Transaction::default().confidential_output_count(), 0);
+    }
+
+    #[test]
+    fn confidential_with_proofs() {
+        let asset = AssetCommitment::from_commitment(0x0a, &[1; 32]).unwrap();
+        let value = ValueCommitment::from_commitment(0x09, &[2; 32]).unwrap();
+        let nonce = Nonce::Confidential(NonceCommitment::from_commitment(0x02, &[3; 32]).unwrap());
+        let script_pubkey = hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1");
+
+        let output = TxOut::new_confidential_with_proofs(
+            asset,
+            value,
+            Some(nonce),
+            script_pubkey.clone(),
+            vec![0x04; 300],
+            vec![0x05; 67],
+        );
+        let confidential = output.as_confidential().unwrap();
+        assert_eq!(
+            (confidential.asset, confidential.value, confidential.nonce),
+            (asset, value, Some(nonce))
+        );
+        assert_eq!(confidential.script_pubkey, script_pubkey);
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output: OutPoint::default(),
+                is_pegin: false,
+                has_issuance: false,
+                script_sig: Script::new(),
+                sequence: 0xffff_ffff,
+                asset_issuance: AssetIssuance::default(),
+                witness: TxInWitness::default(),
+            }],
+            output: vec![output],
+        };
+        let encoded = serialize(&tx);
+
+        // the proofs are in the output witness at the very end of the transaction, not in the
+        // output itself
+        let mut witness = vec![67];
+        witness.extend_from_slice(&[0x05; 67]);
+        witness.extend_from_slice(&[0xfd, 0x2c, 0x01]);
+        witness.extend_from_slice(&[0x04; 300]);
+        assert!(encoded.ends_with(&witness));
+        assert_eq!(
+            serialize(&tx.output[0]).len(),
+            tx.output[0].encoded_length()
+        );
+        assert_eq!(encode::deserialize::<Transaction>(&encoded).unwrap(), tx);
+    }
+
+    #[test]
+    fn txout_null_fields() {
+        // a null output carrying a witness commitment, as found in coinbase transactions
+        let null: TxOut = hex_deserialize!(
+            "000000266a24aa21a9ede2f61c3f71d1defd3fa999dfa36953755c690689799962b48bebd836974e8cf9"
+        );
+        assert!(null.is_null());
+        assert!(null.script_pubkey().is_op_return());
+        assert_eq!(null.explicit_asset(), None);
+        assert_eq!(null.explicit_value(), None);
+        assert_eq!(null.nonce(), None);
+        assert!(!null.is_fee());
+
+        let asset = AssetId::from_slice(&[1; 32]).unwrap();
+        let explicit = TxOut::new_explicit(asset, 1_000, Script::new());
+        assert!(!explicit.is_null());
+        assert_eq!(explicit.explicit_asset(), Some(asset));
+        assert_eq!(explicit.explicit_value(), Some(1_000));
+        assert_eq!(explicit.nonce(), None);
+
+        let nonce = Nonce::Confidential(NonceCommitment::from_commitment(0x02, &[5; 32]).unwrap());
+        let confidential = TxOut::Confidential(ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[3; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[4; 32]).unwrap(),
+            nonce: Some(nonce),
+            script_pubkey: Script::new(),
+            witness: TxOutWitness::default(),
+        });
+        assert!(!confidential.is_null());
+        assert_eq!(confidential.explicit_asset(), None);
+        assert_eq!(confidential.explicit_value(), None);
+        assert_eq!(confidential.nonce(), Some(nonce));
+    }
+
+    #[test]
+    fn txout_prefix_bytes() {
+        let script_pubkey = hex_script!("0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1");
+        let asset_id = AssetId::from_slice(&[1; 32]).unwrap();
+
+        let explicit = TxOut::new_explicit(asset_id, 1_000, script_pubkey.clone());
+        assert_eq!(explicit.prefix_bytes(), (0x01, 0x01, 0x00));
+
+        let null = TxOut::Null(NullTxOut {
+            script_pubkey: script_pubkey.clone(),
+        });
+        assert_eq!(null.prefix_bytes(), (0x00, 0x00, 0x00));
+
+        let confidential = |asset_prefix, value_prefix, nonce| {
+            TxOut::Confidential(ConfidentialTxOut {
+                asset: AssetCommitment::from_commitment(asset_prefix, &[2; 32]).unwrap(),
+                value: ValueCommitment::from_commitment(value_prefix, &[3; 32]).unwrap(),
+                nonce,
+                script_pubkey: script_pubkey.clone(),
+                witness: TxOutWitness::default(),
+            })
+        };
+        let nonce = |prefix| {
+            Some(Nonce::Confidential(
+                NonceCommitment::from_commitment(prefix, &[4; 32]).unwrap(),
+            ))
+        };
+
+        assert_eq!(
+            confidential(0x0a, 0x08, nonce(0x02)).prefix_bytes(),
+            (0x0a, 0x08, 0x02)
+        );
+        assert_eq!(
+            confidential(0x0b, 0x09, nonce(0x03)).prefix_bytes(),
+            (0x0b, 0x09, 0x03)
+        );
+        assert_eq!(
+            confidential(0x0b, 0x08, None).prefix_bytes(),
+            (0x0b, 0x08, 0x00)
+        );
+    }
+
+    #[test]
+    fn txout_explicit_nonce() {
+        let output = ConfidentialTxOut {
+            asset: AssetCommitment::from_commitment(0x0a, &[2; 32]).unwrap(),
+            value: ValueCommitment::from_commitment(0x08, &[3; 32]).unwrap(),
+            nonce: Some(Nonce::Explicit([4; 32])),
+            script_pubkey: Script::new(),
+            witness: TxOutWitness::default(),
+        };
+        let bytes = serialize(&output);
+        assert_eq!(bytes[66], 0x01);
+        assert_eq!(bytes[67..99], [4; 32]);
+        assert_eq!(
+            bytes.len(),
+            TxOut::Confidential(output.clone()).encoded_length()
+        );
+
+        let decoded = encode::deserialize::<TxOut>(&bytes).unwrap();
+        assert_eq!(decoded, TxOut::Confidential(output));
+        assert_eq!(decoded.prefix_bytes().2, 0x01);
+        assert!(matches!(
+            decoded
+                .as_confidential()
+                .unwrap()
+                .unblind(SecretKey::from_slice(&[1; 32]).unwrap()),
+            Err(UnblindError::MissingNonce)
+        ));
+
+        // truncated right before the nonce
+        assert!(matches!(
+            encode::deserialize::<TxOut>(&bytes[..66]),
+            Err(encode::Error::Bitcoin(bitcoin::consensus::encode::Error::Io(ref e)))
+                if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn balance() {
+        let asset_a = AssetId::from_slice(&[1; 32]).unwrap();
+        let asset_b = AssetId::from_slice(&[2; 32]).unwrap();
+
+        let mut balance = Balance::new();
+        assert!(balance.is_balanced());
+
+        balance.add(asset_a, 1_000).unwrap();
+        balance.add(asset_b, 500).unwrap();
+        balance.sub(asset_a, 900).unwrap();
+        balance.sub(asset_a, 100).unwrap();
+        assert_eq!(balance.get(asset_a), 0);
+        assert!(!balance.is_balanced());
+
+        assert_eq!(
+            balance.sub(asset_b, 600),
+            Err(BalanceError::Underflow {
+                asset: asset_b,
+                missing: 100
+            })
+        );
+        assert_eq!(balance.get(asset_b), 500);
+        assert_eq!(
+            balance.add(asset_b, u64::MAX),
+            Err(BalanceError::Overflow(asset_b))
+        );
+
+        balance.sub(asset_b, 500).unwrap();
+        assert!(balance.is_balanced());
+
+        // a failed subtraction does not record the asset
+        let asset_c = AssetId::from_slice(&[3; 32]).unwrap();
+        let before = balance.clone();
+        assert_eq!(
+            balance.sub(asset_c, 1),
+            Err(BalanceError::Underflow {
+                asset: asset_c,
+                missing: 1
+            })
+        );
+        assert_eq!(balance, before);
+    }
+
+    #[test]
+    #[should_panic(expected = "feerate must not be negative or NaN")]
+    fn minimum_fee_nan() {
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![],
+            output: vec![],
+        };
+
+        tx.minimum_fee(f64::NAN, true);
+    }
+
+    #[test]
+    fn sum_unblinded_inputs() {
+        let asset_a = AssetId::from_slice(&[1; 32]).unwrap();
+        let asset_b = AssetId::from_slice(&[2; 32]).unwrap();
+        let outpoint = |vout| OutPoint::new(Txid::from_slice(&[3; 32]).unwrap(), vout);
+        let unblinded = |asset, value| UnblindedTxOut {
+            asset,
+            value,
+            asset_blinding_factor: AssetBlindingFactor::from([4; 32]),
+            value_blinding_factor: ValueBlindingFactor::from([5; 32]),
+        };
+        let input = |vout| TxIn {
+            previous_output: outpoint(vout),
+            is_pegin: false,
+            has_issuance: false,
+            script_sig: Script::new(),
+            sequence: 0xffff_ffff,
+            asset_issuance: AssetIssuance::default(),
+            witness: TxInWitness::default(),
+        };
+
+        let tx = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![input(0), input(1), input(2)],
+            output: vec![],
+        };
+
+        let mut spent_outputs = HashMap::new();
+        spent_outputs.insert(outpoint(0), unblinded(asset_a, 1_000));
+        spent_outputs.insert(outpoint(1), unblinded(asset_b, 2_000));
+
+        assert_eq!(
+            tx.sum_unblinded(&spent_outputs),
+            Err(SumUnblindedError::MissingSpentOutput(outpoint(2)))
+        );
+
+        spent_outputs.insert(outpoint(2), unblinded(asset_a, 500));
+        let sums = tx.sum_unblinded(&spent_outputs).unwrap();
+
+        assert_eq!(sums.len(), 2);
+        assert_eq!(sums[&asset_a], 1_500);
+        assert_eq!(sums[&asset_b], 2_000);
+
+        spent_outputs.insert(outpoint(2), unblinded(asset_a, u64::MAX));
+        assert_eq!(
+            tx.sum_unblinded(&spent_outputs),
+            Err(SumUnblindedError::Overflow(asset_a))
+        );
+    }
+
     #[test]
     fn txout_null_data() {
         // Output with high opcodes should not be considered nulldata
@@ -647,6 +647,28 @@ mod tests {
         )
         .unwrap();
 
+        // the nonce we derive from the vector has to unblind the rangeproof created by elementsd
+        let nonce = crate::confidential::ecdh_nonce_recv(our_sk, sender_pk.into()).unwrap();
+        assert_eq!(
+            hex::encode(nonce),
+            "dcd8f4ddd48f60449490df7548abb9712f3085238177a5e312190dadd758fb2d"
+        );
+        assert_eq!(
+            asset_unblind_with_nonce(
+                nonce.to_vec(),
+                rangeproof,
+                crate::encode::serialize(&value_commitment),
+                script,
+                crate::encode::serialize(&asset_commitment),
+            ),
+            (
+                asset.into_inner().0,
+                abf.into_inner(),
+                vbf.into_inner(),
+                value
+            )
+        );
+
         assert_eq!(
             asset,
             AssetId::from_hex("25b251070e29ca19043cf33ccd7324e2ddab03ecc4ae0b5e77c4fc0e5cf6c95a")
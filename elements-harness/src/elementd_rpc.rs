@@ -27,6 +27,13 @@ pub trait ElementsRpc {
     async fn dumpassetlabels(&self) -> HashMap<String, AssetId>;
     async fn getrawtransaction(&self, txid: Txid) -> String;
     async fn sendrawtransaction(&self, tx_hex: String) -> Txid;
+    async fn signrawtransactionwithkey(
+        &self,
+        tx_hex: String,
+        privkeys: Vec<String>,
+        prevtxs: Option<Vec<serde_json::Value>>,
+        sighashtype: Option<String>,
+    ) -> SignRawTransactionResponse;
     async fn issueasset(
         &self,
         asset_amount: f64,
@@ -103,6 +110,28 @@ impl Client {
         let txid = self.sendrawtransaction(tx_hex).await?;
         Ok(txid)
     }
+
+    /// Have elementsd sign the inputs of `tx` it can sign with `private_key`, given in WIF,
+    /// using `sighash_type` as named in its RPC interface, e.g. `ALL|ANYONECANPAY`.
+    pub async fn sign_raw_transaction_with_key(
+        &self,
+        tx: &Transaction,
+        private_key: String,
+        sighash_type: &str,
+    ) -> Result<Transaction> {
+        let tx_hex = serialize_hex(tx);
+        let response = self
+            .signrawtransactionwithkey(
+                tx_hex,
+                vec![private_key],
+                None,
+                Some(sighash_type.to_owned()),
+            )
+            .await?;
+        let tx = elements_fun::encode::deserialize(&Vec::<u8>::from_hex(&response.hex)?)?;
+
+        Ok(tx)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -111,6 +140,12 @@ pub struct BlockchainInfo {
     mediantime: u32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SignRawTransactionResponse {
+    pub hex: String,
+    pub complete: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct IssueAssetResponse {
     pub txid: Txid,
@@ -124,6 +159,13 @@ pub struct IssueAssetResponse {
 mod test {
     use super::*;
     use crate::Elementsd;
+    use elements_fun::{
+        bip143::SigHashCache,
+        bitcoin::{self, PrivateKey},
+        secp256k1::{Message, PublicKey, Secp256k1, SecretKey, Signature},
+        AddressParams, AssetIssuance, OutPoint, PubkeyHash, Script, SigHashType, TxIn, TxInWitness,
+        TxOut,
+    };
     use testcontainers::clients::Cli;
 
     #[tokio::test]
@@ -206,4 +248,90 @@ mod test {
 
         assert!((balance - expected_balance).abs() < error_margin)
     }
+
+    #[tokio::test]
+    async fn sighash_matches_elementsd_signatures() {
+        let tc_client = Cli::default();
+        let (client, _container) = {
+            let blockchain = Elementsd::new(&tc_client, "0.18.1.9").unwrap();
+
+            (
+                Client::new(blockchain.node_url.clone().into_string()).unwrap(),
+                blockchain,
+            )
+        };
+
+        let secp = Secp256k1::new();
+        let sk = SecretKey::from_slice(&[1; 32]).unwrap();
+        let pk = bitcoin::PublicKey {
+            compressed: true,
+            key: PublicKey::from_secret_key(&secp, &sk),
+        };
+        let wif = PrivateKey {
+            compressed: true,
+            network: bitcoin::Network::Regtest,
+            key: sk,
+        }
+        .to_wif();
+        let address = Address::p2wpkh(&pk, None, &AddressParams::ELEMENTS);
+
+        let asset = client.get_bitcoin_asset_id().await.unwrap();
+        let txid = client
+            .send_asset_to_address(address.clone(), Amount::ONE_BTC, Some(asset))
+            .await
+            .unwrap();
+        let funding = client.get_raw_transaction(txid).await.unwrap();
+        let vout = funding
+            .output
+            .iter()
+            .position(|output| output.script_pubkey() == &address.script_pubkey())
+            .unwrap();
+        let value = funding.output[vout].explicit_value().unwrap();
+
+        let spend = Transaction {
+            version: 2,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(txid, vout as u32),
+                is_pegin: false,
+                has_issuance: false,
+                script_sig: Script::new(),
+                sequence: 0xffff_fffe,
+                asset_issuance: AssetIssuance::default(),
+                witness: TxInWitness::default(),
+            }],
+            output: vec![
+                TxOut::new_explicit(asset, value - 1_000, address.script_pubkey()),
+                TxOut::new_explicit(asset, 1_000, Script::new()),
+            ],
+        };
+        let script_code = Script::new_p2pkh(&PubkeyHash::hash(&pk.to_bytes()));
+
+        for (name, sighash_type) in &[
+            ("ALL", SigHashType::ALL),
+            ("NONE", SigHashType::NONE),
+            ("SINGLE", SigHashType::SINGLE),
+            ("ALL|ANYONECANPAY", SigHashType::ALL_PLUS_ANYONECANPAY),
+            ("NONE|ANYONECANPAY", SigHashType::NONE_PLUS_ANYONECANPAY),
+            ("SINGLE|ANYONECANPAY", SigHashType::SINGLE_PLUS_ANYONECANPAY),
+        ] {
+            let signed = client
+                .sign_raw_transaction_with_key(&spend, wif.clone(), name)
+                .await
+                .unwrap();
+            let witness = &signed.input[0].witness.script_witness;
+            let (&sighash_byte, signature) = witness[0].split_last().unwrap();
+            assert_eq!(u32::from(sighash_byte), sighash_type.as_u32(), "{}", name);
+
+            let sighash =
+                SigHashCache::new(&spend).signature_hash(0, &script_code, value, *sighash_type);
+            let message = Message::from_slice(&sighash[..]).unwrap();
+            let signature = Signature::from_der(signature).unwrap();
+            assert!(
+                secp.verify(&message, &signature, &pk.key).is_ok(),
+                "elementsd signed a different sighash for {}",
+                name
+            );
+        }
+    }
 }